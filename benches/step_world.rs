@@ -0,0 +1,109 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use shared::block_layouts::BlockLayout;
+use shared::game::{self, GameMode, GameState, PlayerAction, PlayerInput, MAX_BALLS_PER_PLAYER};
+use shared::match_settings::MatchSettings;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const ALLOCATION_SAMPLE_TICKS: u64 = 1000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+struct Scenario {
+    name: &'static str,
+    block_layout: BlockLayout,
+    balls_per_player: u8,
+}
+
+const SCENARIOS: [Scenario; 3] = [
+    Scenario {
+        name: "sparse_blocks",
+        block_layout: BlockLayout::Checkerboard,
+        balls_per_player: 1,
+    },
+    Scenario {
+        name: "dense_blocks",
+        block_layout: BlockLayout::Grid,
+        balls_per_player: 1,
+    },
+    Scenario {
+        name: "many_balls",
+        block_layout: BlockLayout::Grid,
+        balls_per_player: MAX_BALLS_PER_PLAYER,
+    },
+];
+
+fn create_launched_world(scenario: &Scenario) -> GameState {
+    let match_settings = MatchSettings {
+        balls_per_player: scenario.balls_per_player,
+        training_ball: true,
+        ..MatchSettings::default()
+    };
+
+    let mut state = game::create_world(scenario.block_layout, GameMode::Versus, match_settings);
+
+    let launch_inputs = [0, 1].map(|player_id| PlayerInput {
+        player_id,
+        action: PlayerAction::Launch,
+    });
+
+    for _ in 0..scenario.balls_per_player {
+        game::step_world(&mut state, &launch_inputs);
+    }
+
+    state
+}
+
+fn report_allocations(scenario: &Scenario) {
+    let mut state = create_launched_world(scenario);
+
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    for _ in 0..ALLOCATION_SAMPLE_TICKS {
+        game::step_world(&mut state, &[]);
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+    println!(
+        "{}: {:.1} allocations per tick",
+        scenario.name,
+        allocations as f64 / ALLOCATION_SAMPLE_TICKS as f64
+    );
+}
+
+fn bench_step_world(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("step_world");
+    group.throughput(Throughput::Elements(1));
+
+    for scenario in SCENARIOS.iter() {
+        report_allocations(scenario);
+
+        let mut state = create_launched_world(scenario);
+
+        group.bench_function(scenario.name, |bencher| {
+            bencher.iter(|| game::step_world(&mut state, &[]))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_step_world);
+criterion_main!(benches);
@@ -0,0 +1,159 @@
+use raylib::color::Color;
+use raylib::drawing::RaylibDraw;
+use raylib::text::measure_text;
+use std::f32::consts::PI;
+
+const PERSPECTIVE_FONT_SIZE: i32 = 20;
+const PERSPECTIVE_PADDING: i32 = 8;
+const VIEW_ROTATION_SECONDS: f32 = 0.6;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CameraPerspective {
+    Player0,
+    Player1,
+    Neutral,
+}
+
+impl CameraPerspective {
+    pub fn next(self) -> Self {
+        match self {
+            CameraPerspective::Player0 => CameraPerspective::Player1,
+            CameraPerspective::Player1 => CameraPerspective::Neutral,
+            CameraPerspective::Neutral => CameraPerspective::Player0,
+        }
+    }
+
+    // Player 0 already sees the world in server coordinates, so only player 1's view is rotated.
+    pub fn is_view_flipped(self) -> bool {
+        self == CameraPerspective::Player1
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CameraPerspective::Player0 => "Player 0 view",
+            CameraPerspective::Player1 => "Player 1 view",
+            CameraPerspective::Neutral => "Neutral view",
+        }
+    }
+}
+
+pub struct ViewRotation {
+    angle: f32,
+    is_animated: bool,
+}
+
+impl ViewRotation {
+    pub fn new(is_animated: bool) -> Self {
+        ViewRotation {
+            angle: 0.0,
+            is_animated,
+        }
+    }
+
+    // Returns the rotation to draw this frame, turning toward the flipped or upright view so a
+    // change of orientation reads as a camera move instead of a jump.
+    pub fn update(&mut self, is_view_flipped: bool, frame_seconds: f32) -> f32 {
+        let target_angle = if is_view_flipped { PI } else { 0.0 };
+
+        if !self.is_animated {
+            self.angle = target_angle;
+            return self.angle;
+        }
+
+        let max_step = PI / VIEW_ROTATION_SECONDS * frame_seconds;
+        let difference = target_angle - self.angle;
+
+        self.angle += difference.clamp(-max_step, max_step);
+        self.angle
+    }
+}
+
+pub fn draw_perspective_overlay(
+    draw_handle: &mut impl RaylibDraw,
+    perspective: CameraPerspective,
+    screen_width: i32,
+) {
+    let label = perspective.label();
+    let width = measure_text(label, PERSPECTIVE_FONT_SIZE) + PERSPECTIVE_PADDING * 2;
+    let height = PERSPECTIVE_FONT_SIZE + PERSPECTIVE_PADDING * 2;
+    let left = screen_width - width;
+
+    draw_handle.draw_rectangle(left, 0, width, height, Color::BLACK.fade(0.6));
+    draw_handle.draw_text(
+        label,
+        left + PERSPECTIVE_PADDING,
+        PERSPECTIVE_PADDING,
+        PERSPECTIVE_FONT_SIZE,
+        Color::WHITE,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perspectives_cycle_back_to_player_0() {
+        let mut perspective = CameraPerspective::Player0;
+        let mut labels = vec![];
+
+        for _ in 0..3 {
+            labels.push(perspective.label());
+            perspective = perspective.next();
+        }
+
+        assert!(perspective == CameraPerspective::Player0);
+        assert_eq!(
+            labels,
+            vec!["Player 0 view", "Player 1 view", "Neutral view"]
+        );
+    }
+
+    #[test]
+    fn only_player_1_perspective_is_flipped() {
+        assert!(!CameraPerspective::Player0.is_view_flipped());
+        assert!(CameraPerspective::Player1.is_view_flipped());
+        assert!(!CameraPerspective::Neutral.is_view_flipped());
+    }
+
+    #[test]
+    fn animated_flip_turns_gradually_and_stops_at_half_turn() {
+        let mut view_rotation = ViewRotation::new(true);
+        let frame_seconds = VIEW_ROTATION_SECONDS / 4.0;
+        let mut previous_angle = view_rotation.update(false, frame_seconds);
+
+        assert_eq!(previous_angle, 0.0);
+
+        for _ in 0..3 {
+            let angle = view_rotation.update(true, frame_seconds);
+
+            assert!(angle > previous_angle && angle < PI, "{}", angle);
+            previous_angle = angle;
+        }
+
+        assert!((view_rotation.update(true, frame_seconds) - PI).abs() < 1e-4);
+        assert!((view_rotation.update(true, frame_seconds) - PI).abs() < 1e-4);
+    }
+
+    #[test]
+    fn flip_back_mid_transition_reverses_from_the_current_angle() {
+        let mut view_rotation = ViewRotation::new(true);
+        let frame_seconds = VIEW_ROTATION_SECONDS / 4.0;
+
+        view_rotation.update(true, frame_seconds);
+        let halfway_angle = view_rotation.update(true, frame_seconds);
+
+        let angle = view_rotation.update(false, frame_seconds);
+
+        assert!(angle < halfway_angle && angle > 0.0, "{}", angle);
+        assert_eq!(view_rotation.update(false, frame_seconds * 10.0), 0.0);
+    }
+
+    #[test]
+    fn unanimated_flip_jumps_straight_to_the_target() {
+        let mut view_rotation = ViewRotation::new(false);
+
+        assert_eq!(view_rotation.update(true, 0.001), PI);
+        assert_eq!(view_rotation.update(false, 0.001), 0.0);
+    }
+}
@@ -0,0 +1,129 @@
+use shared::server_message::DisconnectReason;
+use std::fmt;
+use std::io::ErrorKind;
+use wtransport::error::{ConnectingError, ConnectionError, StreamOpeningError};
+
+#[derive(Debug)]
+pub enum ClientError {
+    NetworkClosed,
+    Disconnected(DisconnectReason),
+    IncompatibleProtocol {
+        server_version: u16,
+        client_version: u16,
+    },
+    Decode(rmp_serde::decode::Error),
+    Encode(rmp_serde::encode::Error),
+    Transport(String),
+    UntrustedServer(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NetworkClosed => write!(f, "Server closed the connection"),
+            ClientError::Disconnected(reason) => {
+                write!(f, "Server ended the session: {}", reason.description())
+            }
+            ClientError::IncompatibleProtocol {
+                server_version,
+                client_version,
+            } => write!(
+                f,
+                "Server speaks protocol version {}, but this client expects {}",
+                server_version, client_version
+            ),
+            ClientError::Decode(e) => write!(f, "Failed to decode server message: {}", e),
+            ClientError::Encode(e) => write!(f, "Failed to encode client message: {}", e),
+            ClientError::Transport(message) => write!(f, "Transport error: {}", message),
+            ClientError::UntrustedServer(message) => write!(
+                f,
+                "The server's certificate was not trusted ({}). Pass the hash the server logs at \
+                 startup with --server-cert-hash, or use --insecure for a local test server",
+                message
+            ),
+            ClientError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<std::io::Error> for ClientError {
+    fn from(error: std::io::Error) -> Self {
+        if error.kind() == ErrorKind::UnexpectedEof {
+            ClientError::NetworkClosed
+        } else {
+            ClientError::Io(error)
+        }
+    }
+}
+
+impl From<rmp_serde::decode::Error> for ClientError {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        ClientError::Decode(error)
+    }
+}
+
+impl From<rmp_serde::encode::Error> for ClientError {
+    fn from(error: rmp_serde::encode::Error) -> Self {
+        ClientError::Encode(error)
+    }
+}
+
+impl From<ConnectingError> for ClientError {
+    fn from(error: ConnectingError) -> Self {
+        ClientError::Transport(error.to_string())
+    }
+}
+
+impl From<ConnectionError> for ClientError {
+    fn from(error: ConnectionError) -> Self {
+        ClientError::Transport(error.to_string())
+    }
+}
+
+impl From<StreamOpeningError> for ClientError {
+    fn from(error: StreamOpeningError) -> Self {
+        ClientError::Transport(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unexpected_eof_converts_to_network_closed() {
+        let error: ClientError = std::io::Error::from(ErrorKind::UnexpectedEof).into();
+
+        assert!(matches!(error, ClientError::NetworkClosed));
+    }
+
+    #[test]
+    fn other_io_errors_keep_their_kind() {
+        let error: ClientError = std::io::Error::from(ErrorKind::ConnectionReset).into();
+
+        match error {
+            ClientError::Io(io_error) => assert_eq!(io_error.kind(), ErrorKind::ConnectionReset),
+            other => panic!("Expected an I/O error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn malformed_frame_converts_to_decode_error() {
+        let error: ClientError = rmp_serde::from_slice::<u32>(&[0xc1]).unwrap_err().into();
+
+        assert!(matches!(error, ClientError::Decode(_)));
+        assert!(error
+            .to_string()
+            .starts_with("Failed to decode server message"));
+    }
+
+    #[test]
+    fn disconnect_reason_is_shown_to_the_player() {
+        let error = ClientError::Disconnected(DisconnectReason::Kicked("idle".to_string()));
+
+        assert_eq!(error.to_string(), "Server ended the session: Kicked: idle");
+    }
+}
@@ -0,0 +1,181 @@
+use raylib::color::Color;
+use raylib::consts::KeyboardKey;
+use raylib::drawing::RaylibDraw;
+use raylib::text::measure_text;
+use raylib::RaylibHandle;
+use shared::constants::{WORLD_HEIGHT, WORLD_WIDTH};
+use std::time::{Duration, Instant};
+
+const CONTROL_HINT_DURATION: Duration = Duration::from_secs(8);
+const CONTROL_HINT_FONT_SIZE: i32 = 30;
+
+pub const HELP_KEY: KeyboardKey = KeyboardKey::KEY_H;
+pub const DEBUG_KEY: KeyboardKey = KeyboardKey::KEY_F3;
+pub const CAMERA_KEY: KeyboardKey = KeyboardKey::KEY_V;
+pub const SCREENSHOT_KEY: KeyboardKey = KeyboardKey::KEY_F2;
+
+pub struct KeyBinding {
+    pub key: KeyboardKey,
+    pub action: &'static str,
+    pub repeats_while_held: bool,
+}
+
+pub const KEY_BINDINGS: [KeyBinding; 5] = [
+    KeyBinding {
+        key: KeyboardKey::KEY_LEFT,
+        action: "move left",
+        repeats_while_held: true,
+    },
+    KeyBinding {
+        key: KeyboardKey::KEY_RIGHT,
+        action: "move right",
+        repeats_while_held: true,
+    },
+    KeyBinding {
+        key: KeyboardKey::KEY_SPACE,
+        action: "launch",
+        repeats_while_held: true,
+    },
+    KeyBinding {
+        key: KeyboardKey::KEY_P,
+        action: "pause",
+        repeats_while_held: false,
+    },
+    KeyBinding {
+        key: KeyboardKey::KEY_R,
+        action: "ready",
+        repeats_while_held: false,
+    },
+];
+
+pub struct ControlHint {
+    is_visible: bool,
+    hide_at: Option<Instant>,
+}
+
+impl ControlHint {
+    pub fn new() -> Self {
+        ControlHint {
+            is_visible: true,
+            hide_at: Some(Instant::now() + CONTROL_HINT_DURATION),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+
+    pub fn update(&mut self, handle: &RaylibHandle) {
+        if handle.is_key_pressed(HELP_KEY) {
+            self.is_visible = !self.is_visible;
+            self.hide_at = None;
+            return;
+        }
+
+        let is_bound_key_pressed = KEY_BINDINGS.iter().any(|b| handle.is_key_pressed(b.key));
+        let is_expired = self
+            .hide_at
+            .is_some_and(|hide_at| Instant::now() >= hide_at);
+
+        if is_bound_key_pressed || is_expired {
+            self.is_visible = false;
+            self.hide_at = None;
+        }
+    }
+}
+
+/// Swaps left and right when the controls are flipped, leaving every other key as it is.
+pub fn mapped_key(key: KeyboardKey, is_controls_flipped: bool) -> KeyboardKey {
+    match key {
+        KeyboardKey::KEY_LEFT if is_controls_flipped => KeyboardKey::KEY_RIGHT,
+        KeyboardKey::KEY_RIGHT if is_controls_flipped => KeyboardKey::KEY_LEFT,
+        key => key,
+    }
+}
+
+/// Player 1 sees the arena rotated by default, so their paddle is at the bottom of the screen.
+pub fn is_view_flipped_by_default(flip_view: Option<bool>, player_id: u8) -> bool {
+    flip_view.unwrap_or(player_id == 1)
+}
+
+pub fn draw_control_hint(draw_handle: &mut impl RaylibDraw, text_color: Color) {
+    let mut lines: Vec<String> = KEY_BINDINGS
+        .iter()
+        .map(|b| format!("{} - {}", key_name(b.key), b.action))
+        .collect();
+
+    lines.push(format!("{} - show or hide this help", key_name(HELP_KEY)));
+    lines.push(format!("{} - show or hide debug info", key_name(DEBUG_KEY)));
+    lines.push(format!(
+        "{} - cycle camera perspective",
+        key_name(CAMERA_KEY)
+    ));
+    lines.push(format!("{} - save a screenshot", key_name(SCREENSHOT_KEY)));
+
+    let line_height = CONTROL_HINT_FONT_SIZE + 10;
+    let top = WORLD_HEIGHT as i32 / 2 + 60;
+
+    for (index, line) in lines.iter().enumerate() {
+        draw_handle.draw_text(
+            line,
+            WORLD_WIDTH as i32 / 2 - measure_text(line, CONTROL_HINT_FONT_SIZE) / 2,
+            top + index as i32 * line_height,
+            CONTROL_HINT_FONT_SIZE,
+            text_color,
+        );
+    }
+}
+
+fn key_name(key: KeyboardKey) -> &'static str {
+    match key {
+        KeyboardKey::KEY_LEFT => "Left",
+        KeyboardKey::KEY_RIGHT => "Right",
+        KeyboardKey::KEY_UP => "Up",
+        KeyboardKey::KEY_DOWN => "Down",
+        KeyboardKey::KEY_SPACE => "Space",
+        KeyboardKey::KEY_ENTER => "Enter",
+        KeyboardKey::KEY_P => "P",
+        KeyboardKey::KEY_R => "R",
+        KeyboardKey::KEY_H => "H",
+        KeyboardKey::KEY_F2 => "F2",
+        KeyboardKey::KEY_F3 => "F3",
+        KeyboardKey::KEY_V => "V",
+        _ => "?",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flipped_controls_swap_only_left_and_right() {
+        assert_eq!(
+            mapped_key(KeyboardKey::KEY_LEFT, true),
+            KeyboardKey::KEY_RIGHT
+        );
+        assert_eq!(
+            mapped_key(KeyboardKey::KEY_RIGHT, true),
+            KeyboardKey::KEY_LEFT
+        );
+
+        for binding in KEY_BINDINGS.iter() {
+            assert_eq!(mapped_key(binding.key, false), binding.key);
+
+            if !matches!(binding.key, KeyboardKey::KEY_LEFT | KeyboardKey::KEY_RIGHT) {
+                assert_eq!(mapped_key(binding.key, true), binding.key);
+            }
+        }
+    }
+
+    #[test]
+    fn view_is_flipped_for_player_1_unless_overridden() {
+        assert!(!is_view_flipped_by_default(None, 0));
+        assert!(is_view_flipped_by_default(None, 1));
+
+        for player_id in [0, 1] {
+            assert!(is_view_flipped_by_default(Some(true), player_id));
+            assert!(!is_view_flipped_by_default(Some(false), player_id));
+        }
+    }
+}
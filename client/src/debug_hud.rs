@@ -0,0 +1,186 @@
+use crate::interpolation::InterpolationSample;
+use crate::network::NetworkStats;
+use cgmath::InnerSpace;
+use raylib::color::Color;
+use raylib::drawing::RaylibDraw;
+use raylib::text::measure_text;
+use shared::world_data::WorldData;
+
+const DEBUG_HUD_FONT_SIZE: i32 = 20;
+const DEBUG_HUD_PADDING: i32 = 8;
+
+pub fn draw_debug_hud(
+    draw_handle: &mut impl RaylibDraw,
+    world_data: &WorldData,
+    fps: u32,
+    network_stats: &NetworkStats,
+    interpolation_sample: InterpolationSample,
+) {
+    let lines = debug_hud_lines(world_data, fps, network_stats, interpolation_sample);
+
+    let line_height = DEBUG_HUD_FONT_SIZE + 4;
+    let panel_width = lines
+        .iter()
+        .map(|line| measure_text(line, DEBUG_HUD_FONT_SIZE))
+        .max()
+        .unwrap_or(0)
+        + DEBUG_HUD_PADDING * 2;
+    let panel_height = lines.len() as i32 * line_height + DEBUG_HUD_PADDING * 2;
+
+    draw_handle.draw_rectangle(0, 0, panel_width, panel_height, Color::BLACK.fade(0.6));
+
+    for (index, line) in lines.iter().enumerate() {
+        draw_handle.draw_text(
+            line,
+            DEBUG_HUD_PADDING,
+            DEBUG_HUD_PADDING + index as i32 * line_height,
+            DEBUG_HUD_FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
+
+fn debug_hud_lines(
+    world_data: &WorldData,
+    fps: u32,
+    network_stats: &NetworkStats,
+    interpolation_sample: InterpolationSample,
+) -> Vec<String> {
+    let interpolation_line = match interpolation_sample.to_tick {
+        Some(to_tick) => format!(
+            "Interp: tick {} -> {}, alpha {:.2}",
+            interpolation_sample.from_tick, to_tick, interpolation_sample.alpha
+        ),
+        None => format!("Interp: holding tick {}", interpolation_sample.from_tick),
+    };
+
+    let mut lines = vec![
+        format!("FPS {} | Tick {}", fps, world_data.tick),
+        format!(
+            "Net: {} B/s, {} snapshots/s, {} dropped, {} late",
+            network_stats.bytes_per_second,
+            network_stats.snapshots_per_second,
+            network_stats.dropped_snapshots,
+            network_stats.late_snapshots
+        ),
+        interpolation_line,
+    ];
+
+    for ball in &world_data.balls {
+        lines.push(format!(
+            "Ball {} (owner {}): pos ({:.1}, {:.1}) vel ({:.1}, {:.1}) speed {:.1} free {}",
+            ball.id,
+            ball.owner,
+            ball.position.x,
+            ball.position.y,
+            ball.velocity.x,
+            ball.velocity.y,
+            ball.velocity.magnitude(),
+            ball.is_free
+        ));
+    }
+
+    for paddle in &world_data.paddles {
+        lines.push(format!(
+            "Paddle {}: pos ({:.1}, {:.1})",
+            paddle.id, paddle.position.x, paddle.position.y
+        ));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector2;
+    use shared::world_data::{Ball, Paddle};
+
+    fn create_world_data() -> WorldData {
+        WorldData {
+            tick: 42,
+            blocks: vec![],
+            regenerating_blocks: vec![],
+            paddles: vec![Paddle {
+                id: 0,
+                position: Vector2::new(960.0, 1060.0),
+                respawn_countdown: None,
+            }],
+            balls: vec![Ball {
+                id: 3,
+                owner: 1,
+                position: Vector2::new(100.3, 200.5),
+                velocity: Vector2::new(0.6, -0.8),
+                is_free: true,
+                scoring: true,
+                attached_ticks: 0,
+                serve_countdown: None,
+                breakthrough_ticks: 0,
+                paddle_collision_cooldown_ticks: 0,
+            }],
+            layout_name: "grid".to_string(),
+            level_index: None,
+            survival_seconds: None,
+            scores: None,
+            mercy_player: None,
+            ready_players: None,
+            waiting_for_players: None,
+            rally_countdown: None,
+            match_countdown: None,
+            reconnect_grace_seconds: None,
+            is_paused: false,
+        }
+    }
+
+    #[test]
+    fn lines_describe_every_ball_and_paddle() {
+        let network_stats = NetworkStats {
+            bytes_per_second: 2048,
+            snapshots_per_second: 60,
+            dropped_snapshots: 2,
+            late_snapshots: 1,
+            ..NetworkStats::default()
+        };
+        let interpolation_sample = InterpolationSample {
+            from_tick: 40,
+            to_tick: Some(41),
+            alpha: 0.25,
+        };
+
+        let lines = debug_hud_lines(
+            &create_world_data(),
+            59,
+            &network_stats,
+            interpolation_sample,
+        );
+
+        assert_eq!(
+            lines,
+            vec![
+                "FPS 59 | Tick 42",
+                "Net: 2048 B/s, 60 snapshots/s, 2 dropped, 1 late",
+                "Interp: tick 40 -> 41, alpha 0.25",
+                "Ball 3 (owner 1): pos (100.3, 200.5) vel (0.6, -0.8) speed 1.0 free true",
+                "Paddle 0: pos (960.0, 1060.0)",
+            ]
+        );
+    }
+
+    #[test]
+    fn interpolation_line_shows_a_held_snapshot() {
+        let interpolation_sample = InterpolationSample {
+            from_tick: 42,
+            to_tick: None,
+            alpha: 0.0,
+        };
+
+        let lines = debug_hud_lines(
+            &create_world_data(),
+            60,
+            &NetworkStats::default(),
+            interpolation_sample,
+        );
+
+        assert_eq!(lines[2], "Interp: holding tick 42");
+    }
+}
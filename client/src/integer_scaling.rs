@@ -0,0 +1,53 @@
+use raylib::color::Color;
+use raylib::drawing::RaylibDraw;
+use raylib::math::{Rectangle, Vector2};
+use raylib::texture::RenderTexture2D;
+
+pub fn integer_scale(
+    screen_width: i32,
+    screen_height: i32,
+    logical_width: i32,
+    logical_height: i32,
+) -> i32 {
+    (screen_width / logical_width)
+        .min(screen_height / logical_height)
+        .max(1)
+}
+
+// Without integer scaling the frame is stretched by the largest factor that still fits the window,
+// which is what a DPI-scaled window needs.
+pub fn draw_scaled(
+    draw_handle: &mut impl RaylibDraw,
+    render_texture: &RenderTexture2D,
+    screen_width: i32,
+    screen_height: i32,
+    is_integer: bool,
+) {
+    let texture = render_texture.texture();
+
+    let scale = if is_integer {
+        integer_scale(screen_width, screen_height, texture.width, texture.height) as f32
+    } else {
+        (screen_width as f32 / texture.width as f32)
+            .min(screen_height as f32 / texture.height as f32)
+    };
+
+    let scaled_width = texture.width as f32 * scale;
+    let scaled_height = texture.height as f32 * scale;
+
+    draw_handle.clear_background(Color::BLACK);
+
+    draw_handle.draw_texture_pro(
+        texture,
+        Rectangle::new(0.0, 0.0, texture.width as f32, -(texture.height as f32)),
+        Rectangle::new(
+            ((screen_width as f32 - scaled_width) / 2.0).floor(),
+            ((screen_height as f32 - scaled_height) / 2.0).floor(),
+            scaled_width,
+            scaled_height,
+        ),
+        Vector2::zero(),
+        0.0,
+        Color::WHITE,
+    );
+}
@@ -0,0 +1,116 @@
+use shared::world_data::WorldData;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy)]
+pub struct InterpolationSample {
+    pub from_tick: u64,
+    pub to_tick: Option<u64>,
+    pub alpha: f32,
+}
+
+// Rendering further in the past makes it more likely that two snapshots surround the render
+// time, which hides jitter, at the cost of showing every moving object that much later.
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<(Instant, WorldData)>,
+    delay: Duration,
+    last_sample: InterpolationSample,
+}
+
+impl SnapshotBuffer {
+    pub fn new(delay: Duration, initial_world_data: WorldData) -> Self {
+        SnapshotBuffer {
+            last_sample: InterpolationSample {
+                from_tick: initial_world_data.tick,
+                to_tick: None,
+                alpha: 0.0,
+            },
+            snapshots: VecDeque::from([(Instant::now(), initial_world_data)]),
+            delay,
+        }
+    }
+
+    pub fn push(&mut self, received_at: Instant, world_data: WorldData) {
+        self.snapshots.push_back((received_at, world_data));
+    }
+
+    pub fn sample(&mut self, now: Instant) -> WorldData {
+        if self.delay.is_zero() {
+            self.snapshots.drain(..self.snapshots.len() - 1);
+
+            self.last_sample = InterpolationSample {
+                from_tick: self.snapshots[0].1.tick,
+                to_tick: None,
+                alpha: 0.0,
+            };
+
+            return self.snapshots[0].1.clone();
+        }
+
+        let render_time = now.checked_sub(self.delay).unwrap_or(now);
+
+        while self.snapshots.len() > 2 && self.snapshots[1].0 <= render_time {
+            self.snapshots.pop_front();
+        }
+
+        let latest_blocks = self.snapshots.back().unwrap().1.blocks.clone();
+
+        let (from_time, from) = &self.snapshots[0];
+
+        let mut world_data = match self.snapshots.get(1) {
+            Some((to_time, to)) if render_time > *from_time => {
+                let span = to_time.duration_since(*from_time).as_secs_f32();
+                let elapsed = render_time.duration_since(*from_time).as_secs_f32();
+
+                let alpha = if span > 0.0 {
+                    (elapsed / span).min(1.0)
+                } else {
+                    1.0
+                };
+
+                self.last_sample = InterpolationSample {
+                    from_tick: from.tick,
+                    to_tick: Some(to.tick),
+                    alpha,
+                };
+
+                interpolate(from, to, alpha)
+            }
+            _ => {
+                self.last_sample = InterpolationSample {
+                    from_tick: from.tick,
+                    to_tick: None,
+                    alpha: 0.0,
+                };
+
+                from.clone()
+            }
+        };
+
+        world_data.blocks = latest_blocks;
+
+        world_data
+    }
+
+    pub fn last_sample(&self) -> InterpolationSample {
+        self.last_sample
+    }
+}
+
+fn interpolate(from: &WorldData, to: &WorldData, alpha: f32) -> WorldData {
+    let mut world_data = from.clone();
+
+    for paddle in world_data.paddles.iter_mut() {
+        if let Some(target) = to.paddles.iter().find(|p| p.id == paddle.id) {
+            paddle.position += (target.position - paddle.position) * alpha;
+        }
+    }
+
+    for ball in world_data.balls.iter_mut() {
+        if let Some(target) = to.balls.iter().find(|b| b.id == ball.id) {
+            ball.position += (target.position - ball.position) * alpha;
+        }
+    }
+
+    world_data
+}
@@ -1,166 +1,1233 @@
+mod camera;
+mod client_error;
+mod controls;
+mod debug_hud;
+mod integer_scaling;
+mod interpolation;
+mod network;
+mod particles;
+mod textures;
+mod theme;
+
+use camera::{CameraPerspective, ViewRotation};
 use cgmath::Vector2;
+use client_error::ClientError;
+use controls::ControlHint;
+use interpolation::SnapshotBuffer;
+use network::{ConnectionState, NetworkStats, ServerTrust, Session};
+use particles::BlockParticles;
 use raylib::color::Color;
 use raylib::consts::KeyboardKey;
+use raylib::core::window::{get_current_monitor, get_monitor_height, get_monitor_width};
 use raylib::drawing::RaylibDraw;
 use raylib::init;
-use shared::constants::{
-    BALL_RADIUS, BLOCK_SIZE, PADDLE_HEIGHT, PADDLE_WIDTH, WORLD_HEIGHT, WORLD_WIDTH,
-};
-use shared::world_data::WorldData;
-use std::error::Error;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use wtransport::Endpoint;
-use wtransport::{ClientConfig, RecvStream, SendStream};
+use raylib::text::measure_text;
+use raylib::{RaylibHandle, RaylibThread};
+use shared::client_message::{ClientMessage, ViewRegion};
+use shared::constants::{BLOCK_SIZE, PADDLE_HEIGHT, WORLD_HEIGHT, WORLD_WIDTH};
+use shared::game;
+use shared::match_settings::GoalLine;
+use shared::server_info::ServerInfo;
+use shared::server_message::ServerMessage;
+use shared::view::{rotate_180_around_world_center, rotate_around_world_center};
+use shared::world_data::{Paddle, WorldData};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use textures::{TexturePaths, Textures};
+use theme::Theme;
+use tokio::sync::{mpsc, watch};
+use wtransport::SendStream;
+
+const MAX_INTERPOLATION_DELAY_MS: u64 = 1000;
+const MAX_FPS_CAP: u32 = 1000;
+const DPI_SCALE_RANGE: RangeInclusive<f32> = 0.5..=4.0;
+const GOAL_LINE_SEGMENTS: usize = 64;
+const REGENERATING_BLOCK_MAX_ALPHA: f32 = 0.5;
+const MATCH_COUNTDOWN_FONT_SIZE: i32 = 200;
+const PADDLE_LABEL_FONT_SIZE: i32 = 20;
+const PADDLE_LABEL_GAP: i32 = 10;
+
+struct ClientArguments {
+    view_region: ViewRegion,
+    server_trust: ServerTrust,
+    texture_paths: TexturePaths,
+    interpolation_delay: Duration,
+    integer_scaling: bool,
+    dpi_scale: f32,
+    vsync: bool,
+    fps_cap: Option<u32>,
+    theme: Theme,
+    flip_view: Option<bool>,
+    flip_controls: bool,
+    animate_view: bool,
+    particles: bool,
+    screenshot_directory: PathBuf,
+}
 
 #[tokio::main]
 async fn main() {
-    let config = ClientConfig::builder()
-        .with_bind_default()
-        .with_no_cert_validation()
-        .build();
-
-    let connection = Endpoint::client(config)
-        .unwrap()
-        .connect("https://localhost:4433")
-        .await
-        .unwrap();
+    let client_arguments = match parse_client_arguments(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let result =
+        match network::open_session(client_arguments.view_region, client_arguments.server_trust)
+            .await
+        {
+            Ok(session) => start_game_loop(session, client_arguments).await,
+            Err(e) => Err(e),
+        };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn parse_client_arguments(
+    arguments: impl Iterator<Item = String>,
+) -> Result<ClientArguments, String> {
+    let mut client_arguments = ClientArguments {
+        view_region: ViewRegion::Full,
+        server_trust: ServerTrust::SystemRoots,
+        texture_paths: TexturePaths::default(),
+        interpolation_delay: Duration::ZERO,
+        integer_scaling: false,
+        dpi_scale: 1.0,
+        vsync: true,
+        fps_cap: None,
+        theme: Theme::default(),
+        flip_view: None,
+        flip_controls: false,
+        animate_view: true,
+        particles: true,
+        screenshot_directory: PathBuf::from("."),
+    };
+
+    let mut arguments = arguments;
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--own-half-only" => client_arguments.view_region = ViewRegion::OwnHalf,
+            "--integer-scaling" => client_arguments.integer_scaling = true,
+            "--insecure" => client_arguments.server_trust = ServerTrust::Insecure,
+            "--server-cert-hash" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+
+                let hash = parse_certificate_hash(&value).ok_or_else(|| {
+                    format!(
+                        "{} expects a SHA-256 hash as 64 hex digits, optionally separated by ':', got '{}'",
+                        argument, value
+                    )
+                })?;
+
+                client_arguments.server_trust = ServerTrust::CertificateHash(hash);
+            }
+            "--no-vsync" => client_arguments.vsync = false,
+            "--dpi-scale" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+
+                let dpi_scale: f32 = value
+                    .parse()
+                    .map_err(|_| format!("{} expects a number, got '{}'", argument, value))?;
+
+                if !DPI_SCALE_RANGE.contains(&dpi_scale) {
+                    return Err(format!(
+                        "{} must be between {} and {}, got {}",
+                        argument,
+                        DPI_SCALE_RANGE.start(),
+                        DPI_SCALE_RANGE.end(),
+                        dpi_scale
+                    ));
+                }
+
+                client_arguments.dpi_scale = dpi_scale;
+            }
+            "--fps-cap" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+
+                let fps_cap: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "{} expects a whole number of frames, got '{}'",
+                        argument, value
+                    )
+                })?;
+
+                if !(1..=MAX_FPS_CAP).contains(&fps_cap) {
+                    return Err(format!(
+                        "{} must be between 1 and {}, got {}",
+                        argument, MAX_FPS_CAP, fps_cap
+                    ));
+                }
+
+                client_arguments.fps_cap = Some(fps_cap);
+            }
+            "--flip-view" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+                client_arguments.flip_view = Some(parse_toggle_argument(&argument, &value)?);
+            }
+            "--flip-controls" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+                client_arguments.flip_controls = parse_toggle_argument(&argument, &value)?;
+            }
+            "--particles" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+                client_arguments.particles = parse_toggle_argument(&argument, &value)?;
+            }
+            "--animate-view" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+                client_arguments.animate_view = parse_toggle_argument(&argument, &value)?;
+            }
+            "--theme" => {
+                let path = next_argument_value(&mut arguments, &argument)?;
+                client_arguments.theme = theme::load_theme(&path)?;
+            }
+            "--background-texture" => {
+                client_arguments.texture_paths.background =
+                    Some(next_argument_value(&mut arguments, &argument)?)
+            }
+            "--block-texture" => {
+                client_arguments.texture_paths.block =
+                    Some(next_argument_value(&mut arguments, &argument)?)
+            }
+            "--paddle-texture" => {
+                client_arguments.texture_paths.paddle =
+                    Some(next_argument_value(&mut arguments, &argument)?)
+            }
+            "--ball-texture" => {
+                client_arguments.texture_paths.ball =
+                    Some(next_argument_value(&mut arguments, &argument)?)
+            }
+            "--screenshot-dir" => {
+                client_arguments.screenshot_directory =
+                    PathBuf::from(next_argument_value(&mut arguments, &argument)?)
+            }
+            "--interpolation-delay-ms" => {
+                let value = next_argument_value(&mut arguments, &argument)?;
+
+                let delay_ms: u64 = value.parse().map_err(|_| {
+                    format!(
+                        "{} expects a whole number of milliseconds, got '{}'",
+                        argument, value
+                    )
+                })?;
+
+                if delay_ms > MAX_INTERPOLATION_DELAY_MS {
+                    return Err(format!(
+                        "{} must be at most {}, got {}",
+                        argument, MAX_INTERPOLATION_DELAY_MS, delay_ms
+                    ));
+                }
+
+                client_arguments.interpolation_delay = Duration::from_millis(delay_ms);
+            }
+            _ => return Err(format!("Unknown argument '{}'", argument)),
+        }
+    }
+
+    Ok(client_arguments)
+}
+
+// A large DPI scale can ask for a window bigger than the display, so it's shrunk to fit while
+// keeping the arena's aspect ratio.
+fn fit_window_to_monitor(handle: &mut RaylibHandle) {
+    let monitor = get_current_monitor();
+    let monitor_width = get_monitor_width(monitor);
+    let monitor_height = get_monitor_height(monitor);
+
+    let window_width = handle.get_screen_width();
+    let window_height = handle.get_screen_height();
+
+    if monitor_width <= 0 || monitor_height <= 0 {
+        return;
+    }
+
+    if window_width <= monitor_width && window_height <= monitor_height {
+        return;
+    }
+
+    let factor = (monitor_width as f32 / window_width as f32)
+        .min(monitor_height as f32 / window_height as f32);
 
-    let (send_stream, receive_stream) = connection.open_bi().await.unwrap().await.unwrap();
-    start_game_loop(send_stream, receive_stream).await.unwrap();
+    handle.set_window_size(
+        (window_width as f32 * factor) as i32,
+        (window_height as f32 * factor) as i32,
+    );
+}
+
+fn parse_certificate_hash(value: &str) -> Option<[u8; 32]> {
+    let digits: String = value.chars().filter(|c| *c != ':').collect();
+
+    if digits.len() != 64 || !digits.is_ascii() {
+        return None;
+    }
+
+    let mut hash = [0; 32];
+
+    for (index, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16).ok()?;
+    }
+
+    Some(hash)
+}
+
+fn parse_toggle_argument(flag: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(format!("{} expects 'on' or 'off', got '{}'", flag, value)),
+    }
+}
+
+fn next_argument_value(
+    arguments: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<String, String> {
+    arguments
+        .next()
+        .ok_or_else(|| format!("Missing value for '{}'", flag))
 }
 
 async fn start_game_loop(
-    mut send_stream: SendStream,
-    mut receive_stream: RecvStream,
-) -> Result<(), Box<dyn Error>> {
-    let player_id = receive_stream.read_u8().await?;
+    session: Session,
+    client_arguments: ClientArguments,
+) -> Result<(), ClientError> {
+    let Session {
+        connection,
+        mut send_stream,
+        mut receive_stream,
+        player_id,
+        server_info,
+    } = session;
+
     println!("Connected as Player {}", player_id);
 
-    let mut world_data: WorldData;
+    println!(
+        "Server mode: {} ({} players), layout: {}, arena: {}x{}",
+        server_info.active_mode,
+        server_info.player_capacity,
+        server_info.active_layout,
+        server_info.arena_width,
+        server_info.arena_height
+    );
+
+    println!(
+        "Match seed: {}, settings: {:?}",
+        server_info.match_settings.random_seed, server_info.match_settings
+    );
 
-    loop {
-        match read_world_data(&mut receive_stream).await {
-            Ok(Some(data)) => {
-                world_data = data;
-                break;
+    let initial_world_data = loop {
+        match network::read_server_message(&mut receive_stream).await {
+            Ok(Some((ServerMessage::WorldData(data), _))) => break data,
+            Ok(Some((ServerMessage::Disconnect(reason), _))) => {
+                return Err(ClientError::Disconnected(reason));
             }
-            _ => continue,
+            Err(ClientError::NetworkClosed) => return Err(ClientError::NetworkClosed),
+            _ => {}
         }
+    };
+
+    let mut snapshot_buffer = SnapshotBuffer::new(
+        client_arguments.interpolation_delay,
+        initial_world_data.clone(),
+    );
+
+    let mut block_particles = client_arguments
+        .particles
+        .then(|| BlockParticles::new(&initial_world_data));
+
+    let (world_data_sender, mut world_data_receiver) = watch::channel(initial_world_data);
+    let (connection_state_sender, connection_state_receiver) =
+        watch::channel(ConnectionState::Connected);
+    let (send_stream_sender, mut send_stream_receiver) = mpsc::unbounded_channel();
+    let (network_stats_sender, network_stats_receiver) = watch::channel(NetworkStats::default());
+    let (hud_sender, hud_receiver) = watch::channel(None);
+
+    tokio::spawn(network::run_world_data_reader(
+        connection,
+        receive_stream,
+        client_arguments.view_region,
+        client_arguments.server_trust,
+        world_data_sender,
+        connection_state_sender,
+        send_stream_sender,
+        network_stats_sender,
+        hud_sender,
+    ));
+
+    let mut builder = init();
+
+    builder
+        .size(
+            (WORLD_WIDTH as f32 * client_arguments.dpi_scale) as i32,
+            (WORLD_HEIGHT as f32 * client_arguments.dpi_scale) as i32,
+        )
+        .title("Ping Pong Arkanoid");
+
+    if client_arguments.vsync {
+        builder.vsync();
+    }
+
+    if client_arguments.integer_scaling {
+        builder.resizable();
     }
 
-    let (mut handle, thread) = init()
-        .size(WORLD_WIDTH as i32, WORLD_HEIGHT as i32)
-        .title("Ping Pong Arkanoid")
-        .vsync()
-        .build();
+    let (mut handle, thread) = builder.build();
+
+    let is_dpi_scaled = client_arguments.dpi_scale != 1.0;
+
+    if is_dpi_scaled {
+        fit_window_to_monitor(&mut handle);
+    }
+
+    if let Some(fps_cap) = client_arguments.fps_cap {
+        handle.set_target_fps(fps_cap);
+    }
+
+    let mut render_texture = if client_arguments.integer_scaling || is_dpi_scaled {
+        match handle.load_render_texture(&thread, WORLD_WIDTH as u32, WORLD_HEIGHT as u32) {
+            Ok(render_texture) => Some(render_texture),
+            Err(e) => {
+                eprintln!(
+                    "Warning: failed to create the render texture ({}), window scaling is disabled",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let textures = textures::load_textures(&mut handle, &thread, &client_arguments.texture_paths);
+
+    let default_view_flipped =
+        controls::is_view_flipped_by_default(client_arguments.flip_view, player_id);
+    let mut camera_perspective: Option<CameraPerspective> = None;
+    let mut view_rotation = ViewRotation::new(client_arguments.animate_view);
+
+    let mut input_sequence: u32 = 0;
+    let mut control_hint = ControlHint::new();
+    let mut is_debug_hud_visible = false;
 
     while !handle.window_should_close() {
-        if handle.is_key_down(KeyboardKey::KEY_SPACE) {
-            send_stream.write_u32(KeyboardKey::KEY_SPACE as u32).await?;
-            send_stream.flush().await?;
+        while let Ok(new_send_stream) = send_stream_receiver.try_recv() {
+            send_stream = new_send_stream;
         }
 
-        if handle.is_key_down(KeyboardKey::KEY_LEFT) {
-            send_stream.write_u32(KeyboardKey::KEY_LEFT as u32).await?;
-            send_stream.flush().await?;
+        control_hint.update(&handle);
+
+        if handle.is_key_pressed(controls::DEBUG_KEY) {
+            is_debug_hud_visible = !is_debug_hud_visible;
         }
 
-        if handle.is_key_down(KeyboardKey::KEY_RIGHT) {
-            send_stream.write_u32(KeyboardKey::KEY_RIGHT as u32).await?;
-            send_stream.flush().await?;
+        if handle.is_key_pressed(controls::CAMERA_KEY) {
+            camera_perspective = Some(match camera_perspective {
+                Some(perspective) => perspective.next(),
+                None => CameraPerspective::Player0,
+            });
         }
 
-        match read_world_data(&mut receive_stream).await {
-            Ok(Some(data)) => {
-                world_data = data;
-            }
-            Ok(None) => {
-                // No data available, continue with old data
+        let is_view_flipped =
+            camera_perspective.map_or(default_view_flipped, CameraPerspective::is_view_flipped);
+        let view_angle = view_rotation.update(is_view_flipped, handle.get_frame_time());
+        let show_paddle_labels = camera_perspective == Some(CameraPerspective::Neutral);
+
+        let connection_state = connection_state_receiver.borrow().clone();
+
+        if connection_state == ConnectionState::Connected {
+            if let Err(e) = send_pressed_keys(
+                &handle,
+                &mut send_stream,
+                &mut input_sequence,
+                client_arguments.flip_controls,
+            )
+            .await
+            {
+                eprintln!("Error sending input: {}", e);
             }
-            Err(e) => {
-                eprintln!("Error reading WorldData: {:?}", e);
-                // Handle error, maybe break loop or continue
+        }
+
+        if world_data_receiver.has_changed().unwrap_or(false) {
+            let received_world_data = world_data_receiver.borrow_and_update().clone();
+            snapshot_buffer.push(Instant::now(), received_world_data);
+        }
+
+        let mut world_data = snapshot_buffer.sample(Instant::now());
+
+        if let Some(hud_update) = hud_receiver.borrow().as_ref() {
+            if hud_update.tick > world_data.tick {
+                hud_update.apply_to(&mut world_data);
             }
         }
 
+        if let Some(block_particles) = block_particles.as_mut() {
+            block_particles.detect_destroyed_blocks(&world_data);
+            block_particles.update(handle.get_frame_time(), view_angle);
+        }
+
+        let is_screenshot_requested = handle.is_key_pressed(controls::SCREENSHOT_KEY);
+
+        let screen_width = handle.get_screen_width();
+        let screen_height = handle.get_screen_height();
+        let fps = handle.get_fps();
+
         let mut draw_handle = handle.begin_drawing(&thread);
 
-        draw_handle.clear_background(Color::from_hex("FFF4EA").unwrap());
+        match render_texture.as_mut() {
+            Some(render_texture) => {
+                {
+                    let mut texture_mode = draw_handle.begin_texture_mode(&thread, render_texture);
 
-        for block in world_data.blocks.clone() {
-            let block_position = if player_id == 1 {
-                rotate_180_around_world_center(block.position)
-            } else {
-                block.position
-            };
+                    draw_frame(
+                        &mut texture_mode,
+                        &world_data,
+                        player_id,
+                        view_angle,
+                        &server_info,
+                        &textures,
+                        &client_arguments.theme,
+                        connection_state,
+                        control_hint.is_visible(),
+                        show_paddle_labels,
+                        block_particles.as_ref(),
+                    );
+                }
+
+                integer_scaling::draw_scaled(
+                    &mut draw_handle,
+                    render_texture,
+                    screen_width,
+                    screen_height,
+                    client_arguments.integer_scaling,
+                );
+            }
+            None => draw_frame(
+                &mut draw_handle,
+                &world_data,
+                player_id,
+                view_angle,
+                &server_info,
+                &textures,
+                &client_arguments.theme,
+                connection_state,
+                control_hint.is_visible(),
+                show_paddle_labels,
+                block_particles.as_ref(),
+            ),
+        }
+
+        if let Some(perspective) = camera_perspective {
+            camera::draw_perspective_overlay(&mut draw_handle, perspective, screen_width);
+        }
+
+        if is_debug_hud_visible {
+            debug_hud::draw_debug_hud(
+                &mut draw_handle,
+                &world_data,
+                fps,
+                &network_stats_receiver.borrow(),
+                snapshot_buffer.last_sample(),
+            );
+        }
+
+        drop(draw_handle);
+
+        if is_screenshot_requested {
+            save_screenshot(
+                &handle,
+                &thread,
+                &client_arguments.screenshot_directory,
+                world_data.tick,
+            );
+        }
+    }
+
+    let network_stats = *network_stats_receiver.borrow();
+
+    println!(
+        "Network: {} snapshots ({} bytes) received, {} dropped, {} late",
+        network_stats.total_snapshots,
+        network_stats.total_bytes,
+        network_stats.dropped_snapshots,
+        network_stats.late_snapshots
+    );
 
+    Ok(())
+}
+
+fn save_screenshot(handle: &RaylibHandle, thread: &RaylibThread, directory: &Path, tick: u64) {
+    let path = match prepare_screenshot_path(directory, tick) {
+        Ok(path) => path,
+        Err(message) => {
+            eprintln!("{}", message);
+            return;
+        }
+    };
+
+    let image = handle.load_image_from_screen(thread);
+    image.export_image(&path.to_string_lossy());
+
+    println!("Saved screenshot to {}", path.display());
+}
+
+fn prepare_screenshot_path(directory: &Path, tick: u64) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(directory).map_err(|e| {
+        format!(
+            "Failed to create screenshot directory {}: {}",
+            directory.display(),
+            e
+        )
+    })?;
+
+    Ok(directory.join(format!("tick_{}.png", tick)))
+}
+
+// The label sits level with the paddle, past its outer end, so it stays clear of the lane the
+// ball travels through; it switches sides when the paddle is near the right wall.
+fn draw_paddle_label(
+    draw_handle: &mut impl RaylibDraw,
+    paddle: &Paddle,
+    scores: Option<[u32; 2]>,
+    view_angle: f32,
+    server_info: &ServerInfo,
+    theme: &Theme,
+) {
+    let label = match scores {
+        Some(scores) => format!("Player {} ({})", paddle.id, scores[paddle.id as usize]),
+        None => format!("Player {}", paddle.id),
+    };
+
+    let paddle_position = rotate_around_world_center(paddle.position, view_angle);
+    let half_extent = game::paddle_half_extent(server_info.match_settings.split_paddle_gap) as i32;
+    let label_width = measure_text(&label, PADDLE_LABEL_FONT_SIZE);
+
+    let right_x = paddle_position.x as i32 + half_extent + PADDLE_LABEL_GAP;
+
+    let label_x = if right_x + label_width <= WORLD_WIDTH as i32 {
+        right_x
+    } else {
+        paddle_position.x as i32 - half_extent - PADDLE_LABEL_GAP - label_width
+    };
+
+    draw_handle.draw_text(
+        &label,
+        label_x,
+        paddle_position.y as i32 - PADDLE_LABEL_FONT_SIZE / 2,
+        PADDLE_LABEL_FONT_SIZE,
+        theme.paddle(paddle.id),
+    );
+}
+
+fn draw_frame(
+    draw_handle: &mut impl RaylibDraw,
+    world_data: &WorldData,
+    player_id: u8,
+    view_angle: f32,
+    server_info: &ServerInfo,
+    textures: &Textures,
+    theme: &Theme,
+    connection_state: ConnectionState,
+    show_control_hint: bool,
+    show_paddle_labels: bool,
+    block_particles: Option<&BlockParticles>,
+) {
+    draw_handle.clear_background(theme.background);
+
+    if let Some(texture) = &textures.background {
+        textures::draw_texture_scaled(
+            draw_handle,
+            texture,
+            0.0,
+            0.0,
+            WORLD_WIDTH as f32,
+            WORLD_HEIGHT as f32,
+            Color::WHITE,
+        );
+    }
+
+    if server_info.match_settings.goal_line != GoalLine::Flat {
+        draw_goal_line(draw_handle, server_info, view_angle, theme);
+    }
+
+    for block in world_data.blocks.clone() {
+        let block_position = rotate_around_world_center(block.position, view_angle);
+
+        if let Some(texture) = &textures.block {
+            textures::draw_texture_scaled(
+                draw_handle,
+                texture,
+                block_position.x - BLOCK_SIZE as f32 / 2.0,
+                block_position.y - BLOCK_SIZE as f32 / 2.0,
+                BLOCK_SIZE as f32,
+                BLOCK_SIZE as f32,
+                Color::WHITE,
+            );
+        } else {
             draw_handle.draw_rectangle(
                 block_position.x as i32 - (BLOCK_SIZE as i32 / 2),
                 block_position.y as i32 - (BLOCK_SIZE as i32 / 2),
                 BLOCK_SIZE as i32,
                 BLOCK_SIZE as i32,
-                Color::from_hex("7EACB5").unwrap(),
+                theme.block,
             );
         }
+    }
 
-        for paddle in world_data.paddles.clone() {
-            let paddle_position = if player_id == 1 {
-                rotate_180_around_world_center(paddle.position)
-            } else {
-                paddle.position
-            };
+    for regenerating_block in world_data.regenerating_blocks.iter() {
+        let block_position = rotate_around_world_center(regenerating_block.position, view_angle);
 
-            let paddle_color = if paddle.id == 0 {
-                Color::from_hex("FADFA1").unwrap()
-            } else {
-                Color::from_hex("6A9C89").unwrap()
-            };
+        let alpha = regenerating_block.progress * REGENERATING_BLOCK_MAX_ALPHA;
 
+        if let Some(texture) = &textures.block {
+            textures::draw_texture_scaled(
+                draw_handle,
+                texture,
+                block_position.x - BLOCK_SIZE as f32 / 2.0,
+                block_position.y - BLOCK_SIZE as f32 / 2.0,
+                BLOCK_SIZE as f32,
+                BLOCK_SIZE as f32,
+                Color::WHITE.fade(alpha),
+            );
+        } else {
             draw_handle.draw_rectangle(
-                paddle_position.x as i32 - (PADDLE_WIDTH as i32 / 2),
-                paddle_position.y as i32 - (PADDLE_HEIGHT as i32 / 2),
-                PADDLE_WIDTH as i32,
-                PADDLE_HEIGHT as i32,
-                paddle_color,
+                block_position.x as i32 - (BLOCK_SIZE as i32 / 2),
+                block_position.y as i32 - (BLOCK_SIZE as i32 / 2),
+                BLOCK_SIZE as i32,
+                BLOCK_SIZE as i32,
+                theme.block.fade(alpha),
             );
         }
+    }
+
+    if let Some(block_particles) = block_particles {
+        block_particles.draw(draw_handle, view_angle, theme.block);
+    }
+
+    for paddle in world_data.paddles.clone() {
+        for segment in game::paddle_segments(&paddle, server_info.match_settings.split_paddle_gap) {
+            let segment_position = rotate_around_world_center(segment.position, view_angle);
 
-        for ball in world_data.balls.clone() {
-            let ball_position = if player_id == 1 {
-                rotate_180_around_world_center(ball.position)
+            if let Some(texture) = &textures.paddle {
+                textures::draw_texture_scaled(
+                    draw_handle,
+                    texture,
+                    segment_position.x - segment.width as f32 / 2.0,
+                    segment_position.y - PADDLE_HEIGHT as f32 / 2.0,
+                    segment.width as f32,
+                    PADDLE_HEIGHT as f32,
+                    Color::WHITE,
+                );
             } else {
-                ball.position
-            };
+                draw_handle.draw_rectangle(
+                    segment_position.x as i32 - (segment.width as i32 / 2),
+                    segment_position.y as i32 - (PADDLE_HEIGHT as i32 / 2),
+                    segment.width as i32,
+                    PADDLE_HEIGHT as i32,
+                    theme.paddle(paddle.id),
+                );
+            }
+        }
 
+        if show_paddle_labels {
+            draw_paddle_label(
+                draw_handle,
+                &paddle,
+                world_data.scores,
+                view_angle,
+                server_info,
+                theme,
+            );
+        }
+    }
+
+    for ball in world_data.balls.clone() {
+        let ball_position = rotate_around_world_center(ball.position, view_angle);
+
+        let ball_radius = server_info.match_settings.ball_radius;
+
+        let (ball_color, texture_tint) = if ball.breakthrough_ticks > 0 {
+            (theme.breakthrough_ball, theme.breakthrough_ball)
+        } else if ball.scoring {
+            (theme.ball, Color::WHITE)
+        } else {
+            (theme.training_ball, theme.training_ball)
+        };
+
+        if let Some(texture) = &textures.ball {
+            textures::draw_texture_scaled(
+                draw_handle,
+                texture,
+                ball_position.x - ball_radius,
+                ball_position.y - ball_radius,
+                ball_radius * 2.0,
+                ball_radius * 2.0,
+                texture_tint,
+            );
+        } else {
             draw_handle.draw_circle(
                 ball_position.x as i32,
                 ball_position.y as i32,
-                BALL_RADIUS as f32,
-                Color::from_hex("C96868").unwrap(),
+                ball_radius,
+                ball_color,
             );
         }
     }
 
-    Ok(())
+    draw_handle.draw_text(
+        &match_header_text(server_info, world_data),
+        10,
+        10,
+        20,
+        theme.text,
+    );
+
+    if let Some(survival_seconds) = world_data.survival_seconds {
+        draw_handle.draw_text(
+            &format!("Survived: {:.1}s", survival_seconds),
+            10,
+            40,
+            20,
+            theme.text,
+        );
+    }
+
+    if let Some(scores) = world_data.scores {
+        let opponent_id = 1 - player_id as usize;
+
+        let mercy_text = match world_data.mercy_player {
+            Some(mercy_player) if mercy_player == player_id => " | Mercy: your side is slowed",
+            Some(_) => " | Mercy: opponent's side is slowed",
+            None => "",
+        };
+
+        draw_handle.draw_text(
+            &format!(
+                "Score: {} - {}{}",
+                scores[player_id as usize], scores[opponent_id], mercy_text
+            ),
+            10,
+            40,
+            20,
+            theme.text,
+        );
+    }
+
+    let own_serve_countdown = world_data
+        .balls
+        .iter()
+        .filter(|b| b.owner == player_id)
+        .find_map(|b| b.serve_countdown);
+
+    if let Some(seconds) = own_serve_countdown {
+        let message = format!("Launching in {}...", seconds);
+
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, 40) / 2,
+            WORLD_HEIGHT as i32 - 160,
+            40,
+            theme.warning,
+        );
+    }
+
+    let own_respawn_countdown = world_data
+        .paddles
+        .iter()
+        .find(|p| p.id == player_id)
+        .and_then(|p| p.respawn_countdown);
+
+    if let Some(seconds) = own_respawn_countdown {
+        let message = format!("Respawning in {}...", seconds);
+
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, 40) / 2,
+            WORLD_HEIGHT as i32 - 160,
+            40,
+            theme.warning,
+        );
+    }
+
+    if let Some(seconds) = world_data.rally_countdown {
+        let message = format!("Stalemate, resetting in {}...", seconds);
+
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, 40) / 2,
+            WORLD_HEIGHT as i32 / 2 - 160,
+            40,
+            theme.warning,
+        );
+    }
+
+    if let Some(ready_players) = world_data.ready_players {
+        let message = if !ready_players[player_id as usize] {
+            Some("Press R when ready".to_string())
+        } else {
+            ready_players
+                .iter()
+                .position(|is_ready| !is_ready)
+                .map(|id| format!("Waiting for Player {} to ready up", id))
+        };
+
+        if let Some(message) = message {
+            draw_handle.draw_text(
+                &message,
+                WORLD_WIDTH as i32 / 2 - measure_text(&message, 40) / 2,
+                WORLD_HEIGHT as i32 - 220,
+                40,
+                theme.text,
+            );
+        }
+    }
+
+    if show_control_hint {
+        controls::draw_control_hint(draw_handle, theme.text);
+    }
+
+    if let Some(missing_players) = world_data.waiting_for_players {
+        let message = format!(
+            "Waiting for {} more player{}...",
+            missing_players,
+            if missing_players == 1 { "" } else { "s" }
+        );
+
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, 60) / 2,
+            WORLD_HEIGHT as i32 / 2 - 100,
+            60,
+            theme.text,
+        );
+
+        if let Some(seconds) = world_data.reconnect_grace_seconds {
+            let message = format!("Match abandoned in {}s unless they reconnect", seconds);
+
+            draw_handle.draw_text(
+                &message,
+                WORLD_WIDTH as i32 / 2 - measure_text(&message, 30) / 2,
+                WORLD_HEIGHT as i32 / 2 - 20,
+                30,
+                theme.warning,
+            );
+        }
+    }
+
+    if let Some(seconds) = world_data.match_countdown {
+        let message = seconds.to_string();
+
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, MATCH_COUNTDOWN_FONT_SIZE) / 2,
+            WORLD_HEIGHT as i32 / 2 - MATCH_COUNTDOWN_FONT_SIZE / 2,
+            MATCH_COUNTDOWN_FONT_SIZE,
+            theme.warning,
+        );
+    }
+
+    if world_data.is_paused {
+        let message = "Paused (P to resume)";
+
+        draw_handle.draw_text(
+            message,
+            WORLD_WIDTH as i32 / 2 - measure_text(message, 60) / 2,
+            WORLD_HEIGHT as i32 / 2 - 100,
+            60,
+            theme.text,
+        );
+    }
+
+    let connection_message = match connection_state {
+        ConnectionState::Connected => None,
+        ConnectionState::Reconnecting(attempt) => {
+            Some(format!("Reconnecting... (attempt {})", attempt))
+        }
+        ConnectionState::ClosedByServer => Some("Server closed the connection".to_string()),
+        ConnectionState::Disconnected(reason) => Some(reason.description()),
+        ConnectionState::Lost => Some("Connection lost".to_string()),
+    };
+
+    if let Some(message) = connection_message {
+        draw_handle.draw_text(
+            &message,
+            WORLD_WIDTH as i32 / 2 - measure_text(&message, 60) / 2,
+            WORLD_HEIGHT as i32 / 2 - 30,
+            60,
+            theme.warning,
+        );
+    }
 }
 
-async fn read_world_data(stream: &mut RecvStream) -> Result<Option<WorldData>, Box<dyn Error>> {
-    let len = match stream.read_u32().await {
-        Ok(len) => len,
-        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
-        Err(e) => return Err(Box::new(e)),
+fn match_header_text(server_info: &ServerInfo, world_data: &WorldData) -> String {
+    let level_text = match world_data.level_index {
+        Some(level_index) => format!(" | Level {}", level_index + 1),
+        None => String::new(),
     };
 
-    let mut buffer = vec![0; len as usize];
-    stream.read_exact(&mut buffer).await?;
+    format!(
+        "Mode: {} ({} players) | Layout: {}{} | Seed: {}",
+        server_info.active_mode,
+        server_info.player_capacity,
+        world_data.layout_name,
+        level_text,
+        server_info.match_settings.random_seed
+    )
+}
+
+fn draw_goal_line(
+    draw_handle: &mut impl RaylibDraw,
+    server_info: &ServerInfo,
+    view_angle: f32,
+    theme: &Theme,
+) {
+    let goal_line = server_info.match_settings.goal_line;
+    let color = theme.warning;
+
+    let bottom_points: Vec<Vector2<f32>> = (0..=GOAL_LINE_SEGMENTS)
+        .map(|segment| {
+            let x = WORLD_WIDTH as f32 * segment as f32 / GOAL_LINE_SEGMENTS as f32;
+            Vector2::new(x, WORLD_HEIGHT as f32 - goal_line.inset_at(x))
+        })
+        .collect();
+
+    let mut goal_lines = vec![bottom_points];
+
+    if server_info.player_capacity > 1 {
+        let top_points = goal_lines[0]
+            .iter()
+            .map(|point| rotate_180_around_world_center(*point))
+            .collect();
+
+        goal_lines.push(top_points);
+    }
+
+    for points in &goal_lines {
+        for segment in points.windows(2) {
+            let start = rotate_around_world_center(segment[0], view_angle);
+            let end = rotate_around_world_center(segment[1], view_angle);
+
+            draw_handle.draw_line(
+                start.x as i32,
+                start.y as i32,
+                end.x as i32,
+                end.y as i32,
+                color,
+            );
+        }
+    }
+}
+
+async fn send_pressed_keys(
+    handle: &RaylibHandle,
+    send_stream: &mut SendStream,
+    input_sequence: &mut u32,
+    is_controls_flipped: bool,
+) -> Result<(), ClientError> {
+    for binding in controls::KEY_BINDINGS.iter() {
+        let is_triggered = if binding.repeats_while_held {
+            handle.is_key_down(binding.key)
+        } else {
+            handle.is_key_pressed(binding.key)
+        };
+
+        if is_triggered {
+            let key = controls::mapped_key(binding.key, is_controls_flipped);
 
-    let data = rmp_serde::from_slice(&buffer)?;
-    Ok(Some(data))
+            send_key(send_stream, input_sequence, key).await?;
+        }
+    }
+
+    Ok(())
 }
 
-fn rotate_180_around_world_center(vector: Vector2<f32>) -> Vector2<f32> {
-    let world_center = Vector2::new(WORLD_WIDTH as f32 / 2.0, WORLD_HEIGHT as f32 / 2.0);
-    let translated = vector - world_center;
-    let rotated = Vector2::new(-translated.x, -translated.y);
-    world_center + rotated
+async fn send_key(
+    send_stream: &mut SendStream,
+    input_sequence: &mut u32,
+    key: KeyboardKey,
+) -> Result<(), ClientError> {
+    *input_sequence = input_sequence.wrapping_add(1);
+
+    network::send_client_message(
+        send_stream,
+        ClientMessage::Key {
+            sequence: *input_sequence,
+            key_code: key as u32,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::block_layouts::BlockLayout;
+    use shared::game::GameMode;
+    use shared::match_settings::MatchSettings;
+    use shared::server_info::PROTOCOL_VERSION;
+
+    fn create_server_info(match_settings: MatchSettings) -> ServerInfo {
+        ServerInfo {
+            protocol_version: PROTOCOL_VERSION,
+            available_modes: vec!["versus".to_string()],
+            active_mode: "versus".to_string(),
+            available_layouts: vec!["fortress".to_string()],
+            active_layout: "fortress".to_string(),
+            arena_width: WORLD_WIDTH,
+            arena_height: WORLD_HEIGHT,
+            player_capacity: 2,
+            match_settings,
+        }
+    }
+
+    #[test]
+    fn header_shows_the_seed_the_match_runs_with() {
+        let match_settings = MatchSettings {
+            random_seed: 987654321,
+            ..MatchSettings::default()
+        };
+        let game_state = game::create_world(
+            BlockLayout::Fortress,
+            GameMode::Versus,
+            match_settings.clone(),
+        );
+
+        let header = match_header_text(&create_server_info(match_settings), &game_state.world_data);
+
+        assert_eq!(
+            header,
+            format!(
+                "Mode: versus (2 players) | Layout: fortress | Seed: {}",
+                game_state.match_settings.random_seed
+            )
+        );
+        assert!(header.ends_with("Seed: 987654321"));
+    }
+
+    #[test]
+    fn header_shows_the_level_in_a_level_sequence() {
+        let mut world_data = game::create_world(
+            BlockLayout::Pyramid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        )
+        .world_data;
+        world_data.level_index = Some(1);
+
+        let header = match_header_text(&create_server_info(MatchSettings::default()), &world_data);
+
+        assert!(
+            header.contains("| Layout: pyramid | Level 2 |"),
+            "{}",
+            header
+        );
+    }
+
+    #[test]
+    fn certificate_hash_is_parsed_with_or_without_colons() {
+        let expected: [u8; 32] = std::array::from_fn(|index| (index * 8) as u8);
+        let plain: String = expected
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let dotted = expected
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(":");
+
+        assert_eq!(parse_certificate_hash(&plain), Some(expected));
+        assert_eq!(parse_certificate_hash(&dotted), Some(expected));
+    }
+
+    #[test]
+    fn malformed_certificate_hash_is_rejected() {
+        let valid = "ab".repeat(32);
+
+        for value in [
+            String::new(),
+            valid[..62].to_string(),
+            format!("{}ab", valid),
+            format!("{}zz", &valid[..62]),
+            format!("{}é", &valid[..62]),
+        ] {
+            assert_eq!(parse_certificate_hash(&value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn view_and_controls_flips_are_parsed_independently() {
+        let client_arguments = parse_client_arguments(
+            ["--flip-view", "off", "--flip-controls", "on"]
+                .into_iter()
+                .map(String::from),
+        )
+        .ok()
+        .unwrap();
+
+        assert_eq!(client_arguments.flip_view, Some(false));
+        assert!(client_arguments.flip_controls);
+
+        let client_arguments = parse_client_arguments(std::iter::empty()).ok().unwrap();
+
+        assert_eq!(client_arguments.flip_view, None);
+        assert!(!client_arguments.flip_controls);
+    }
+
+    #[test]
+    fn screenshot_path_is_named_after_the_tick_in_a_created_directory() {
+        let directory = std::env::temp_dir()
+            .join(format!(
+                "ping-pong-arkanoid-screenshots-{}",
+                std::process::id()
+            ))
+            .join("nested");
+
+        let path = prepare_screenshot_path(&directory, 1234).unwrap();
+
+        assert!(directory.is_dir());
+        assert_eq!(path, directory.join("tick_1234.png"));
+
+        std::fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn screenshot_directory_that_is_a_file_is_reported() {
+        let file_path = std::env::temp_dir().join(format!(
+            "ping-pong-arkanoid-screenshot-file-{}",
+            std::process::id()
+        ));
+        std::fs::write(&file_path, "").unwrap();
+
+        let error = prepare_screenshot_path(&file_path, 1).unwrap_err();
+
+        assert!(
+            error.starts_with("Failed to create screenshot directory"),
+            "{}",
+            error
+        );
+
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn screenshot_directory_argument_is_parsed() {
+        let client_arguments = parse_client_arguments(
+            ["--screenshot-dir", "captures"]
+                .into_iter()
+                .map(String::from),
+        )
+        .ok()
+        .unwrap();
+
+        assert_eq!(
+            client_arguments.screenshot_directory,
+            PathBuf::from("captures")
+        );
+    }
 }
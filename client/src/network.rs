@@ -0,0 +1,362 @@
+use crate::client_error::ClientError;
+use shared::client_message::{ClientMessage, ViewRegion};
+use shared::server_info::{ServerInfo, PROTOCOL_VERSION};
+use shared::server_message::{DisconnectReason, HudUpdate, ServerMessage};
+use shared::world_data::WorldData;
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{mpsc, watch};
+use wtransport::error::{ConnectingError, ConnectionError};
+use wtransport::tls::Sha256Digest;
+use wtransport::{ClientConfig, Connection, Endpoint, RecvStream, SendStream};
+
+const SERVER_URL: &str = "https://localhost:4433";
+
+const MAX_READ_ERRORS: usize = 5;
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const NETWORK_STATS_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ServerTrust {
+    SystemRoots,
+    CertificateHash([u8; 32]),
+    Insecure,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting(u32),
+    ClosedByServer,
+    Disconnected(DisconnectReason),
+    Lost,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct NetworkStats {
+    pub bytes_per_second: u64,
+    pub snapshots_per_second: u32,
+    pub total_bytes: u64,
+    pub total_snapshots: u64,
+    pub dropped_snapshots: u64,
+    pub late_snapshots: u64,
+}
+
+struct NetworkStatsCounter {
+    stats: NetworkStats,
+    window_started_at: Instant,
+    window_bytes: u64,
+    window_snapshots: u32,
+    last_tick: Option<u64>,
+}
+
+impl NetworkStatsCounter {
+    fn new() -> Self {
+        NetworkStatsCounter {
+            stats: NetworkStats::default(),
+            window_started_at: Instant::now(),
+            window_bytes: 0,
+            window_snapshots: 0,
+            last_tick: None,
+        }
+    }
+
+    fn record(&mut self, frame_bytes: usize, tick: u64) {
+        self.window_bytes += frame_bytes as u64;
+        self.window_snapshots += 1;
+        self.stats.total_bytes += frame_bytes as u64;
+        self.stats.total_snapshots += 1;
+
+        match self.last_tick {
+            Some(last_tick) if tick <= last_tick => self.stats.late_snapshots += 1,
+            Some(last_tick) => {
+                self.stats.dropped_snapshots += tick - last_tick - 1;
+                self.last_tick = Some(tick);
+            }
+            None => self.last_tick = Some(tick),
+        }
+
+        self.roll_window();
+    }
+
+    fn roll_window(&mut self) {
+        if self.window_started_at.elapsed() < NETWORK_STATS_WINDOW {
+            return;
+        }
+
+        self.stats.bytes_per_second = self.window_bytes;
+        self.stats.snapshots_per_second = self.window_snapshots;
+        self.window_started_at = Instant::now();
+        self.window_bytes = 0;
+        self.window_snapshots = 0;
+    }
+}
+
+pub struct Session {
+    pub connection: Connection,
+    pub send_stream: SendStream,
+    pub receive_stream: RecvStream,
+    pub player_id: u8,
+    pub server_info: ServerInfo,
+}
+
+pub async fn open_session(
+    view_region: ViewRegion,
+    server_trust: ServerTrust,
+) -> Result<Session, ClientError> {
+    let builder = ClientConfig::builder().with_bind_default();
+
+    let config = match server_trust {
+        ServerTrust::SystemRoots => builder.with_native_certs().build(),
+        ServerTrust::CertificateHash(hash) => builder
+            .with_server_certificate_hashes([Sha256Digest::new(hash)])
+            .build(),
+        ServerTrust::Insecure => builder.with_no_cert_validation().build(),
+    };
+
+    let connection = Endpoint::client(config)?
+        .connect(SERVER_URL)
+        .await
+        .map_err(|e| match e {
+            // A certificate the client refuses fails the TLS handshake, which surfaces as a QUIC
+            // protocol error rather than a close or timeout.
+            ConnectingError::ConnectionError(ConnectionError::QuicProto(_))
+                if server_trust != ServerTrust::Insecure =>
+            {
+                ClientError::UntrustedServer(e.to_string())
+            }
+            _ => ClientError::from(e),
+        })?;
+
+    let (mut send_stream, mut receive_stream) = connection.open_bi().await?.await?;
+
+    let player_id = receive_stream.read_u8().await?;
+    let server_info = read_server_info(&mut receive_stream).await?;
+
+    if server_info.protocol_version != PROTOCOL_VERSION {
+        return Err(ClientError::IncompatibleProtocol {
+            server_version: server_info.protocol_version,
+            client_version: PROTOCOL_VERSION,
+        });
+    }
+
+    if view_region != ViewRegion::Full {
+        send_client_message(&mut send_stream, ClientMessage::ViewRegion(view_region)).await?;
+    }
+
+    Ok(Session {
+        connection,
+        send_stream,
+        receive_stream,
+        player_id,
+        server_info,
+    })
+}
+
+pub async fn run_world_data_reader(
+    mut connection: Connection,
+    mut receive_stream: RecvStream,
+    view_region: ViewRegion,
+    server_trust: ServerTrust,
+    world_data_sender: watch::Sender<WorldData>,
+    connection_state_sender: watch::Sender<ConnectionState>,
+    send_stream_sender: mpsc::UnboundedSender<SendStream>,
+    network_stats_sender: watch::Sender<NetworkStats>,
+    hud_sender: watch::Sender<Option<HudUpdate>>,
+) {
+    let mut read_errors_count = 0;
+    let mut network_stats = NetworkStatsCounter::new();
+    let mut static_world_data = world_data_sender.borrow().clone();
+
+    loop {
+        match read_server_message(&mut receive_stream).await {
+            Ok(Some((ServerMessage::Disconnect(reason), _))) => {
+                eprintln!("Server ended the session: {}", reason.description());
+                connection_state_sender.send_replace(ConnectionState::Disconnected(reason));
+                return;
+            }
+            Ok(Some((ServerMessage::Hud(hud_update), frame_bytes))) => {
+                network_stats.record(frame_bytes, hud_update.tick);
+                network_stats_sender.send_replace(network_stats.stats);
+
+                hud_sender.send_replace(Some(hud_update));
+                read_errors_count = 0;
+            }
+            Ok(Some((ServerMessage::WorldData(data), frame_bytes))) => {
+                network_stats.record(frame_bytes, data.tick);
+                network_stats_sender.send_replace(network_stats.stats);
+
+                static_world_data = data.clone();
+                world_data_sender.send_replace(data);
+                read_errors_count = 0;
+            }
+            Ok(Some((ServerMessage::Dynamic(update), frame_bytes))) => {
+                network_stats.record(frame_bytes, update.tick);
+                network_stats_sender.send_replace(network_stats.stats);
+
+                world_data_sender.send_replace(update.merge_with_static(&static_world_data));
+                read_errors_count = 0;
+            }
+            Ok(None) => {
+                // No data available, continue with old data
+            }
+            Err(ClientError::NetworkClosed) => {
+                eprintln!("Server closed the connection");
+                connection_state_sender.send_replace(ConnectionState::ClosedByServer);
+                return;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Error reading WorldData from {}: {}",
+                    connection.remote_address(),
+                    e
+                );
+
+                read_errors_count += 1;
+
+                if read_errors_count < MAX_READ_ERRORS {
+                    continue;
+                }
+
+                match reconnect(view_region, server_trust, &connection_state_sender).await {
+                    Some(session) => {
+                        connection = session.connection;
+                        receive_stream = session.receive_stream;
+                        read_errors_count = 0;
+
+                        let _ = send_stream_sender.send(session.send_stream);
+                        connection_state_sender.send_replace(ConnectionState::Connected);
+                    }
+                    None => {
+                        connection_state_sender.send_replace(ConnectionState::Lost);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn reconnect(
+    view_region: ViewRegion,
+    server_trust: ServerTrust,
+    connection_state_sender: &watch::Sender<ConnectionState>,
+) -> Option<Session> {
+    for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+        connection_state_sender.send_replace(ConnectionState::Reconnecting(attempt));
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+
+        match open_session(view_region, server_trust).await {
+            Ok(session) => {
+                println!("Reconnected as Player {}", session.player_id);
+                return Some(session);
+            }
+            Err(e) => eprintln!("Reconnect attempt {} failed: {}", attempt, e),
+        }
+    }
+
+    None
+}
+
+pub async fn send_client_message(
+    stream: &mut SendStream,
+    message: ClientMessage,
+) -> Result<(), ClientError> {
+    let buf = rmp_serde::to_vec(&message)?;
+    stream.write_u32(buf.len() as u32).await?;
+    stream.write_all(&buf).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_server_info(stream: &mut RecvStream) -> Result<ServerInfo, ClientError> {
+    let len = stream.read_u32().await?;
+
+    let mut buffer = vec![0; len as usize];
+    stream.read_exact(&mut buffer).await?;
+
+    Ok(rmp_serde::from_slice(&buffer)?)
+}
+
+pub async fn read_server_message(
+    stream: &mut (impl AsyncRead + Unpin),
+) -> Result<Option<(ServerMessage, usize)>, ClientError> {
+    let len = match stream.read_u32().await {
+        Ok(len) => len,
+        Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut buffer = vec![0; len as usize];
+    stream.read_exact(&mut buffer).await?;
+
+    let data = rmp_serde::from_slice(&buffer)?;
+    Ok(Some((data, std::mem::size_of::<u32>() + buffer.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_ticks_are_counted_as_dropped() {
+        let mut counter = NetworkStatsCounter::new();
+
+        for tick in [10, 11, 14, 15, 20] {
+            counter.record(100, tick);
+        }
+
+        assert_eq!(counter.stats.dropped_snapshots, 6);
+        assert_eq!(counter.stats.late_snapshots, 0);
+        assert_eq!(counter.stats.total_snapshots, 5);
+        assert_eq!(counter.stats.total_bytes, 500);
+    }
+
+    #[test]
+    fn old_and_repeated_ticks_are_counted_as_late() {
+        let mut counter = NetworkStatsCounter::new();
+
+        for tick in [10, 13, 11, 13, 14] {
+            counter.record(100, tick);
+        }
+
+        assert_eq!(counter.stats.late_snapshots, 2);
+        assert_eq!(counter.stats.dropped_snapshots, 2);
+        assert_eq!(counter.last_tick, Some(14));
+    }
+
+    fn frame(message: &ServerMessage) -> Vec<u8> {
+        let payload = rmp_serde::to_vec(message).unwrap();
+        let mut frame = (payload.len() as u32).to_be_bytes().to_vec();
+        frame.extend(payload);
+        frame
+    }
+
+    #[tokio::test]
+    async fn terminal_disconnect_message_is_classified() {
+        for reason in [
+            DisconnectReason::MatchEnded,
+            DisconnectReason::ServerShuttingDown,
+            DisconnectReason::Kicked("idle".to_string()),
+        ] {
+            let bytes = frame(&ServerMessage::Disconnect(reason.clone()));
+            let mut stream = bytes.as_slice();
+
+            match read_server_message(&mut stream).await {
+                Ok(Some((ServerMessage::Disconnect(received_reason), frame_bytes))) => {
+                    assert_eq!(received_reason, reason);
+                    assert_eq!(frame_bytes, bytes.len());
+                }
+                _ => panic!("Expected a disconnect message"),
+            }
+
+            assert!(matches!(
+                read_server_message(&mut stream).await,
+                Err(ClientError::NetworkClosed)
+            ));
+        }
+    }
+}
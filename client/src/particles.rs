@@ -0,0 +1,115 @@
+use cgmath::Vector2;
+use raylib::color::Color;
+use raylib::drawing::RaylibDraw;
+use shared::random::SeededRandom;
+use shared::view::rotate_around_world_center;
+use shared::world_data::{Block, WorldData};
+
+const MAX_PARTICLES: usize = 512;
+
+const PARTICLES_PER_BLOCK: usize = 12;
+const PARTICLE_LIFETIME_SECONDS: f32 = 0.6;
+const PARTICLE_MAX_SPEED: f32 = 240.0;
+const PARTICLE_GRAVITY: f32 = 600.0;
+const PARTICLE_RADIUS: f32 = 3.0;
+
+struct Particle {
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    age_seconds: f32,
+}
+
+pub struct BlockParticles {
+    particles: Vec<Particle>,
+    previous_blocks: Vec<Block>,
+    previous_level: (String, Option<u8>),
+    random: SeededRandom,
+}
+
+impl BlockParticles {
+    pub fn new(world_data: &WorldData) -> Self {
+        BlockParticles {
+            particles: Vec::with_capacity(MAX_PARTICLES),
+            previous_blocks: world_data.blocks.clone(),
+            previous_level: (world_data.layout_name.clone(), world_data.level_index),
+            random: SeededRandom::new(0),
+        }
+    }
+
+    // Blocks are kept ordered by id, so a block that was in the previous frame and can't be found
+    // in this one was destroyed. A level change replaces every block at once and is not a hit.
+    pub fn detect_destroyed_blocks(&mut self, world_data: &WorldData) {
+        let level = (world_data.layout_name.clone(), world_data.level_index);
+
+        if level == self.previous_level {
+            let destroyed_positions: Vec<Vector2<f32>> = self
+                .previous_blocks
+                .iter()
+                .filter(|previous| {
+                    world_data
+                        .blocks
+                        .binary_search_by_key(&previous.id, |block| block.id)
+                        .is_err()
+                })
+                .map(|previous| previous.position)
+                .collect();
+
+            for position in destroyed_positions {
+                self.spawn_burst(position);
+            }
+        }
+
+        self.previous_blocks = world_data.blocks.clone();
+        self.previous_level = level;
+    }
+
+    fn spawn_burst(&mut self, position: Vector2<f32>) {
+        for _ in 0..PARTICLES_PER_BLOCK {
+            let velocity = Vector2::new(
+                self.random.next_signed_unit(),
+                self.random.next_signed_unit(),
+            ) * PARTICLE_MAX_SPEED;
+
+            self.particles.push(Particle {
+                position,
+                velocity,
+                age_seconds: 0.0,
+            });
+        }
+
+        if self.particles.len() > MAX_PARTICLES {
+            let excess = self.particles.len() - MAX_PARTICLES;
+            self.particles.drain(..excess);
+        }
+    }
+
+    // Gravity is applied in world space but always points down the screen, whichever way the view
+    // is currently rotated.
+    pub fn update(&mut self, frame_seconds: f32, view_angle: f32) {
+        let (sin, cos) = view_angle.sin_cos();
+        let gravity = Vector2::new(sin, cos) * PARTICLE_GRAVITY;
+
+        for particle in self.particles.iter_mut() {
+            particle.velocity += gravity * frame_seconds;
+            particle.position += particle.velocity * frame_seconds;
+            particle.age_seconds += frame_seconds;
+        }
+
+        self.particles
+            .retain(|particle| particle.age_seconds < PARTICLE_LIFETIME_SECONDS);
+    }
+
+    pub fn draw(&self, draw_handle: &mut impl RaylibDraw, view_angle: f32, color: Color) {
+        for particle in &self.particles {
+            let position = rotate_around_world_center(particle.position, view_angle);
+            let alpha = 1.0 - particle.age_seconds / PARTICLE_LIFETIME_SECONDS;
+
+            draw_handle.draw_circle(
+                position.x as i32,
+                position.y as i32,
+                PARTICLE_RADIUS,
+                color.fade(alpha),
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use raylib::color::Color;
+use raylib::drawing::RaylibDraw;
+use raylib::math::{Rectangle, Vector2};
+use raylib::texture::Texture2D;
+use raylib::{RaylibHandle, RaylibThread};
+use std::path::Path;
+
+#[derive(Default)]
+pub struct TexturePaths {
+    pub background: Option<String>,
+    pub block: Option<String>,
+    pub paddle: Option<String>,
+    pub ball: Option<String>,
+}
+
+pub struct Textures {
+    pub background: Option<Texture2D>,
+    pub block: Option<Texture2D>,
+    pub paddle: Option<Texture2D>,
+    pub ball: Option<Texture2D>,
+}
+
+pub fn load_textures(
+    handle: &mut RaylibHandle,
+    thread: &RaylibThread,
+    paths: &TexturePaths,
+) -> Textures {
+    Textures {
+        background: load_optional_texture(handle, thread, &paths.background, "background"),
+        block: load_optional_texture(handle, thread, &paths.block, "block"),
+        paddle: load_optional_texture(handle, thread, &paths.paddle, "paddle"),
+        ball: load_optional_texture(handle, thread, &paths.ball, "ball"),
+    }
+}
+
+fn load_optional_texture(
+    handle: &mut RaylibHandle,
+    thread: &RaylibThread,
+    path: &Option<String>,
+    role: &str,
+) -> Option<Texture2D> {
+    let path = path.as_ref()?;
+
+    if !Path::new(path).is_file() {
+        eprintln!(
+            "Warning: {} texture '{}' does not exist, falling back to a solid color",
+            role, path
+        );
+        return None;
+    }
+
+    match handle.load_texture(thread, path) {
+        Ok(texture) => Some(texture),
+        Err(e) => {
+            eprintln!(
+                "Warning: failed to load {} texture '{}' ({}), falling back to a solid color",
+                role, path, e
+            );
+            None
+        }
+    }
+}
+
+pub fn draw_texture_scaled(
+    draw_handle: &mut impl RaylibDraw,
+    texture: &Texture2D,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    tint: Color,
+) {
+    draw_handle.draw_texture_pro(
+        texture,
+        Rectangle::new(0.0, 0.0, texture.width as f32, texture.height as f32),
+        Rectangle::new(x, y, width, height),
+        Vector2::zero(),
+        0.0,
+        tint,
+    );
+}
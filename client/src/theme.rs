@@ -0,0 +1,188 @@
+use raylib::color::Color;
+use serde::Deserialize;
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub background: Color,
+    pub block: Color,
+    pub paddle0: Color,
+    pub paddle1: Color,
+    pub ball: Color,
+    pub training_ball: Color,
+    pub breakthrough_ball: Color,
+    pub text: Color,
+    pub warning: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: Color::from_hex("FFF4EA").unwrap(),
+            block: Color::from_hex("7EACB5").unwrap(),
+            paddle0: Color::from_hex("FADFA1").unwrap(),
+            paddle1: Color::from_hex("6A9C89").unwrap(),
+            ball: Color::from_hex("C96868").unwrap(),
+            training_ball: Color::from_hex("A9A9A9").unwrap(),
+            breakthrough_ball: Color::from_hex("E8A33D").unwrap(),
+            text: Color::from_hex("7EACB5").unwrap(),
+            warning: Color::from_hex("C96868").unwrap(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn paddle(&self, paddle_id: u8) -> Color {
+        if paddle_id == 0 {
+            self.paddle0
+        } else {
+            self.paddle1
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    background: Option<String>,
+    block: Option<String>,
+    paddle0: Option<String>,
+    paddle1: Option<String>,
+    ball: Option<String>,
+    training_ball: Option<String>,
+    breakthrough_ball: Option<String>,
+    text: Option<String>,
+    warning: Option<String>,
+}
+
+pub fn load_theme(path: &str) -> Result<Theme, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read theme file '{}': {}", path, e))?;
+
+    let theme_file: ThemeFile = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid theme file '{}': {}", path, e))?;
+
+    let mut theme = Theme::default();
+
+    let entries = [
+        (&theme_file.background, &mut theme.background, "background"),
+        (&theme_file.block, &mut theme.block, "block"),
+        (&theme_file.paddle0, &mut theme.paddle0, "paddle0"),
+        (&theme_file.paddle1, &mut theme.paddle1, "paddle1"),
+        (&theme_file.ball, &mut theme.ball, "ball"),
+        (
+            &theme_file.training_ball,
+            &mut theme.training_ball,
+            "training_ball",
+        ),
+        (
+            &theme_file.breakthrough_ball,
+            &mut theme.breakthrough_ball,
+            "breakthrough_ball",
+        ),
+        (&theme_file.text, &mut theme.text, "text"),
+        (&theme_file.warning, &mut theme.warning, "warning"),
+    ];
+
+    for (value, color, role) in entries {
+        if let Some(value) = value {
+            *color = parse_hex_color(value).ok_or_else(|| {
+                format!(
+                    "Theme entry '{}' has invalid color '{}', expected RRGGBB hex digits",
+                    role, value
+                )
+            })?;
+        }
+    }
+
+    Ok(theme)
+}
+
+fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Color::from_hex(hex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_theme_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "ping-pong-arkanoid-theme-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load_test_theme(name: &str, contents: &str) -> Result<Theme, String> {
+        let path = write_theme_file(name, contents);
+        let theme = load_theme(path.to_str().unwrap());
+        std::fs::remove_file(path).unwrap();
+        theme
+    }
+
+    #[test]
+    fn hex_colors_parse_with_and_without_hash() {
+        assert_eq!(
+            parse_hex_color("#FF8000"),
+            Some(Color::new(255, 128, 0, 255))
+        );
+        assert_eq!(
+            parse_hex_color("00ff80"),
+            Some(Color::new(0, 255, 128, 255))
+        );
+    }
+
+    #[test]
+    fn malformed_hex_colors_are_rejected() {
+        for value in ["", "#FFF", "FF80001", "GG8000", "#FF 800"] {
+            assert_eq!(parse_hex_color(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn theme_file_overrides_only_listed_colors() {
+        let theme =
+            load_test_theme("partial", r##"{"ball": "#102030", "text": "405060"}"##).unwrap();
+        let default_theme = Theme::default();
+
+        assert_eq!(theme.ball, Color::new(16, 32, 48, 255));
+        assert_eq!(theme.text, Color::new(64, 80, 96, 255));
+        assert_eq!(theme.background, default_theme.background);
+        assert_eq!(theme.paddle0, default_theme.paddle0);
+    }
+
+    #[test]
+    fn invalid_theme_color_names_the_entry() {
+        let error = load_test_theme("invalid-color", r#"{"paddle1": "blue"}"#)
+            .err()
+            .unwrap();
+
+        assert!(error.contains("'paddle1'"), "{}", error);
+        assert!(error.contains("'blue'"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_theme_entries_are_rejected() {
+        let error = load_test_theme("unknown-entry", r##"{"paddle2": "#000000"}"##)
+            .err()
+            .unwrap();
+
+        assert!(error.starts_with("Invalid theme file"), "{}", error);
+    }
+
+    #[test]
+    fn missing_theme_file_is_reported() {
+        let error = load_theme("/nonexistent/theme.json").err().unwrap();
+
+        assert!(error.starts_with("Failed to read theme file"), "{}", error);
+    }
+}
@@ -0,0 +1,172 @@
+use raylib::color::Color;
+use raylib::consts::KeyboardKey;
+use raylib::drawing::RaylibDraw;
+use raylib::init;
+use raylib::RaylibHandle;
+use shared::block_layouts::BlockLayout;
+use shared::constants::{BLOCK_SIZE, PADDLE_HEIGHT, WORLD_HEIGHT, WORLD_WIDTH};
+use shared::game::{self, GameMode, PlayerAction, PlayerInput, GAME_LOOP_TIMESTEP_SECONDS};
+use shared::match_settings::MatchSettings;
+
+const HELD_BINDINGS: [(u8, KeyboardKey, PlayerAction); 6] = [
+    (0, KeyboardKey::KEY_LEFT, PlayerAction::MoveLeft),
+    (0, KeyboardKey::KEY_RIGHT, PlayerAction::MoveRight),
+    (0, KeyboardKey::KEY_SPACE, PlayerAction::Launch),
+    (1, KeyboardKey::KEY_A, PlayerAction::MoveLeft),
+    (1, KeyboardKey::KEY_D, PlayerAction::MoveRight),
+    (1, KeyboardKey::KEY_W, PlayerAction::Launch),
+];
+
+fn main() {
+    let (block_layout, game_mode) = match parse_arguments(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let mut game_state = game::create_world(block_layout, game_mode, MatchSettings::default());
+
+    let (mut handle, thread) = init()
+        .size(WORLD_WIDTH as i32, WORLD_HEIGHT as i32)
+        .title("Ping Pong Arkanoid (local)")
+        .vsync()
+        .build();
+
+    let mut unsimulated_seconds = 0.0;
+
+    while !handle.window_should_close() {
+        let mut inputs = collect_inputs(&handle, game_mode);
+
+        unsimulated_seconds += handle.get_frame_time();
+
+        while unsimulated_seconds >= GAME_LOOP_TIMESTEP_SECONDS {
+            game::step_world(&mut game_state, &inputs);
+
+            inputs.retain(|input| {
+                input.action != PlayerAction::TogglePause && input.action != PlayerAction::Ready
+            });
+
+            unsimulated_seconds -= GAME_LOOP_TIMESTEP_SECONDS;
+        }
+
+        let world_data = &game_state.world_data;
+
+        let mut draw_handle = handle.begin_drawing(&thread);
+
+        draw_handle.clear_background(Color::from_hex("FFF4EA").unwrap());
+
+        for block in &world_data.blocks {
+            draw_handle.draw_rectangle(
+                block.position.x as i32 - (BLOCK_SIZE as i32 / 2),
+                block.position.y as i32 - (BLOCK_SIZE as i32 / 2),
+                BLOCK_SIZE as i32,
+                BLOCK_SIZE as i32,
+                Color::from_hex("7EACB5").unwrap(),
+            );
+        }
+
+        for paddle in &world_data.paddles {
+            let paddle_color = if paddle.id == 0 {
+                Color::from_hex("FADFA1").unwrap()
+            } else {
+                Color::from_hex("6A9C89").unwrap()
+            };
+
+            for segment in game::paddle_segments(paddle, game_state.match_settings.split_paddle_gap)
+            {
+                draw_handle.draw_rectangle(
+                    segment.position.x as i32 - (segment.width as i32 / 2),
+                    segment.position.y as i32 - (PADDLE_HEIGHT as i32 / 2),
+                    segment.width as i32,
+                    PADDLE_HEIGHT as i32,
+                    paddle_color,
+                );
+            }
+        }
+
+        for ball in &world_data.balls {
+            draw_handle.draw_circle(
+                ball.position.x as i32,
+                ball.position.y as i32,
+                game_state.match_settings.ball_radius,
+                Color::from_hex("C96868").unwrap(),
+            );
+        }
+
+        draw_handle.draw_text(
+            &format!(
+                "Local {} | Tick {}{}",
+                game_mode.name(),
+                world_data.tick,
+                if world_data.is_paused {
+                    " | Paused"
+                } else {
+                    ""
+                }
+            ),
+            10,
+            10,
+            20,
+            Color::from_hex("7EACB5").unwrap(),
+        );
+    }
+}
+
+fn parse_arguments(
+    mut arguments: impl Iterator<Item = String>,
+) -> Result<(BlockLayout, GameMode), String> {
+    let mut block_layout = BlockLayout::Grid;
+    let mut game_mode = GameMode::Versus;
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--layout" => {
+                let name = arguments.next().ok_or("--layout requires a value")?;
+
+                block_layout = BlockLayout::from_name(&name)
+                    .ok_or_else(|| format!("Unknown block layout '{}'", name))?;
+            }
+            "--mode" => {
+                let name = arguments.next().ok_or("--mode requires a value")?;
+
+                game_mode = GameMode::ALL
+                    .into_iter()
+                    .find(|m| m.name() == name)
+                    .ok_or_else(|| format!("Unknown game mode '{}'", name))?;
+            }
+            _ => return Err(format!("Unknown argument '{}'", argument)),
+        }
+    }
+
+    Ok((block_layout, game_mode))
+}
+
+fn collect_inputs(handle: &RaylibHandle, game_mode: GameMode) -> Vec<PlayerInput> {
+    let mut inputs: Vec<PlayerInput> = vec![];
+
+    if handle.is_key_pressed(KeyboardKey::KEY_P) {
+        inputs.push(PlayerInput {
+            player_id: 0,
+            action: PlayerAction::TogglePause,
+        });
+    }
+
+    if handle.is_key_pressed(KeyboardKey::KEY_R) {
+        for player_id in 0..game_mode.player_capacity() {
+            inputs.push(PlayerInput {
+                player_id,
+                action: PlayerAction::Ready,
+            });
+        }
+    }
+
+    for (player_id, key, action) in HELD_BINDINGS {
+        if player_id < game_mode.player_capacity() && handle.is_key_down(key) {
+            inputs.push(PlayerInput { player_id, action });
+        }
+    }
+
+    inputs
+}
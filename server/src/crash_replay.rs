@@ -0,0 +1,59 @@
+use serde::Serialize;
+use shared::world_data::WorldData;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub const CRASH_REPLAY_CAPACITY_TICKS: usize = 300;
+pub const CRASH_REPLAY_PATH: &str = "crash_replay.bin";
+
+#[derive(Serialize, Clone)]
+pub struct ReplayInput {
+    pub player_id: u8,
+    pub key_code: u32,
+}
+
+#[derive(Serialize)]
+struct ReplayFrame<'a> {
+    inputs: &'a [ReplayInput],
+    world_data: &'a WorldData,
+}
+
+pub struct CrashReplayBuffer {
+    frames: VecDeque<(Vec<ReplayInput>, WorldData)>,
+    capacity: usize,
+}
+
+impl CrashReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        CrashReplayBuffer {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, inputs: Vec<ReplayInput>, world_data: &WorldData) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back((inputs, world_data.clone()));
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        for (inputs, world_data) in &self.frames {
+            let buffer = rmp_serde::to_vec(&ReplayFrame { inputs, world_data })?;
+
+            writer.write_all(&(buffer.len() as u32).to_be_bytes())?;
+            writer.write_all(&buffer)?;
+        }
+
+        writer.flush()?;
+
+        Ok(self.frames.len())
+    }
+}
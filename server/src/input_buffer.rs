@@ -0,0 +1,176 @@
+use crate::PlayerKeyEvent;
+use std::collections::VecDeque;
+
+const CATCH_UP_TICKS: usize = 4;
+
+pub struct InputBuffer {
+    queues: [VecDeque<(PlayerKeyEvent, bool)>; 2],
+    depth: usize,
+    dropped_inputs: u64,
+}
+
+impl InputBuffer {
+    pub fn new(depth: usize) -> Self {
+        InputBuffer {
+            queues: [
+                VecDeque::with_capacity(depth),
+                VecDeque::with_capacity(depth),
+            ],
+            depth,
+            dropped_inputs: 0,
+        }
+    }
+
+    pub fn dropped_inputs(&self) -> u64 {
+        self.dropped_inputs
+    }
+
+    // Metered inputs are spread over the following ticks, so a burst that arrives after a network
+    // stall doesn't move the paddle at once. Other inputs keep their place behind the metered
+    // inputs that arrived before them.
+    pub fn push(&mut self, event: PlayerKeyEvent, is_metered: bool) {
+        let Some(queue) = self.queues.get_mut(event.player_id as usize) else {
+            return;
+        };
+
+        if is_metered && queue.iter().filter(|(_, is_metered)| *is_metered).count() == self.depth {
+            if let Some(oldest_index) = queue.iter().position(|(_, is_metered)| *is_metered) {
+                queue.remove(oldest_index);
+                self.dropped_inputs += 1;
+            }
+        }
+
+        queue.push_back((event, is_metered));
+    }
+
+    // A larger backlog releases more metered inputs per tick, so it's caught up within about
+    // CATCH_UP_TICKS ticks instead of lagging one tick behind per queued input.
+    pub fn take_tick_inputs(&mut self) -> Vec<PlayerKeyEvent> {
+        let mut inputs = vec![];
+
+        for queue in self.queues.iter_mut() {
+            let queued_metered_inputs = queue.iter().filter(|(_, is_metered)| *is_metered).count();
+            let mut released_metered_inputs = queued_metered_inputs.div_ceil(CATCH_UP_TICKS);
+
+            while let Some((_, is_metered)) = queue.front() {
+                if *is_metered {
+                    if released_metered_inputs == 0 {
+                        break;
+                    }
+
+                    released_metered_inputs -= 1;
+                }
+
+                if let Some((event, _)) = queue.pop_front() {
+                    inputs.push(event);
+                }
+            }
+        }
+
+        inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAUNCH_KEY_CODE: u32 = 100;
+
+    fn push_moves(
+        input_buffer: &mut InputBuffer,
+        player_id: u8,
+        key_codes: impl Iterator<Item = u32>,
+    ) {
+        for key_code in key_codes {
+            input_buffer.push(
+                PlayerKeyEvent {
+                    player_id,
+                    key_code,
+                },
+                true,
+            );
+        }
+    }
+
+    fn push_launch(input_buffer: &mut InputBuffer, player_id: u8) {
+        input_buffer.push(
+            PlayerKeyEvent {
+                player_id,
+                key_code: LAUNCH_KEY_CODE,
+            },
+            false,
+        );
+    }
+
+    fn take_key_codes(input_buffer: &mut InputBuffer) -> Vec<u32> {
+        input_buffer
+            .take_tick_inputs()
+            .into_iter()
+            .map(|event| event.key_code)
+            .collect()
+    }
+
+    #[test]
+    fn burst_is_spread_over_several_ticks_and_caught_up() {
+        let mut input_buffer = InputBuffer::new(60);
+        push_moves(&mut input_buffer, 0, 1..=12);
+
+        let mut released_per_tick = vec![];
+        let mut released_key_codes = vec![];
+
+        loop {
+            let key_codes = take_key_codes(&mut input_buffer);
+
+            if key_codes.is_empty() {
+                break;
+            }
+
+            released_per_tick.push(key_codes.len());
+            released_key_codes.extend(key_codes);
+        }
+
+        assert_eq!(released_key_codes, (1..=12).collect::<Vec<u32>>());
+        assert!(released_per_tick.len() > 1);
+        assert!(released_per_tick.len() < 12);
+        assert!(released_per_tick
+            .iter()
+            .all(|count| *count <= 12_usize.div_ceil(CATCH_UP_TICKS)));
+        assert_eq!(input_buffer.dropped_inputs(), 0);
+    }
+
+    #[test]
+    fn launch_does_not_overtake_earlier_moves() {
+        let mut input_buffer = InputBuffer::new(60);
+        push_moves(&mut input_buffer, 0, 1..=3);
+        push_launch(&mut input_buffer, 0);
+        push_moves(&mut input_buffer, 0, 4..=4);
+
+        assert_eq!(take_key_codes(&mut input_buffer), vec![1]);
+        assert_eq!(take_key_codes(&mut input_buffer), vec![2]);
+        assert_eq!(take_key_codes(&mut input_buffer), vec![3, LAUNCH_KEY_CODE]);
+        assert_eq!(take_key_codes(&mut input_buffer), vec![4]);
+        assert!(take_key_codes(&mut input_buffer).is_empty());
+    }
+
+    #[test]
+    fn launch_without_queued_moves_is_released_at_once() {
+        let mut input_buffer = InputBuffer::new(60);
+        push_moves(&mut input_buffer, 0, 1..=8);
+        push_launch(&mut input_buffer, 1);
+
+        let key_codes = take_key_codes(&mut input_buffer);
+
+        assert_eq!(key_codes, vec![1, 2, LAUNCH_KEY_CODE]);
+    }
+
+    #[test]
+    fn full_buffer_drops_the_oldest_move() {
+        let mut input_buffer = InputBuffer::new(4);
+        push_moves(&mut input_buffer, 1, 1..=6);
+
+        assert_eq!(input_buffer.dropped_inputs(), 2);
+        assert_eq!(take_key_codes(&mut input_buffer), vec![3]);
+        assert_eq!(take_key_codes(&mut input_buffer), vec![4]);
+    }
+}
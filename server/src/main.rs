@@ -1,381 +1,2479 @@
-use cgmath::{AbsDiffEq, Vector2};
-use log::{error, info};
+mod crash_replay;
+mod input_buffer;
+mod network_simulation;
+mod saved_state;
+mod tick_watchdog;
+
+use cgmath::Vector2;
+use crash_replay::{
+    CrashReplayBuffer, ReplayInput, CRASH_REPLAY_CAPACITY_TICKS, CRASH_REPLAY_PATH,
+};
+use input_buffer::InputBuffer;
+use log::{debug, error, info, warn};
+use network_simulation::{NetworkSimulation, MAX_SIMULATED_DROP_PERCENT, MAX_SIMULATED_LATENCY_MS};
 use raylib::consts::KeyboardKey;
-use shared::constants::{
-    BALL_RADIUS, BLOCKS_IN_ROW, BLOCK_SIZE, PADDLE_HEIGHT, PADDLE_WIDTH, WORLD_HEIGHT, WORLD_WIDTH,
+use serde::Serialize;
+use shared::block_layouts::BlockLayout;
+use shared::client_message::{ClientMessage, ViewRegion};
+use shared::constants::{BLOCK_SIZE, PADDLE_HEIGHT, WORLD_HEIGHT, WORLD_WIDTH};
+use shared::game::{
+    self, GameMode, GameState, PlayerAction, PlayerInput, GAME_LOOP_TIMESTEP_SECONDS,
+    MAX_ARENA_EDGE_DEAD_ZONE, MAX_BALLS_PER_PLAYER, MAX_BALL_X_VELOCITY, MAX_SPLIT_PADDLE_GAP,
+};
+use shared::match_settings::{
+    DeflectionCurve, GoalLine, LaunchDirection, MatchSettings, PaddleSideHit,
 };
-use shared::world_data::{Ball, Block, Paddle, WorldData};
+use shared::server_info::{ServerInfo, PROTOCOL_VERSION};
+use shared::server_message::{DisconnectReason, DynamicUpdate, HudUpdate, ServerMessage};
+use shared::world_data::{Block, WorldData};
 use std::error::Error;
-use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tick_watchdog::{TickWatchdog, WatchdogChange, DEGRADED_BROADCAST_INTERVAL_TICKS};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::sync::watch::Receiver;
-use tokio::sync::{mpsc, watch};
-use tracing::info_span;
+use tokio::sync::{mpsc, watch, Mutex as AsyncMutex, Notify};
+use tokio::task::JoinSet;
 use tracing::level_filters::LevelFilter;
 use tracing::Instrument;
+use tracing::{debug_span, info_span, trace_span};
 use tracing_subscriber::EnvFilter;
 use watch::channel;
+use wtransport::endpoint::endpoint_side::Server;
 use wtransport::endpoint::IncomingSession;
-use wtransport::ServerConfig;
+use wtransport::error::ConnectionError;
+use wtransport::tls::Sha256DigestFmt;
 use wtransport::{Endpoint, Identity};
+use wtransport::{SendStream, ServerConfig};
+
+const DEFAULT_KEEP_ALIVE_INTERVAL_SECONDS: u64 = 3;
+const DEFAULT_IDLE_TIMEOUT_SECONDS: u64 = 30;
+const KEEP_ALIVE_INTERVAL_SECONDS_RANGE: RangeInclusive<u64> = 1..=60;
+const IDLE_TIMEOUT_SECONDS_RANGE: RangeInclusive<u64> = 2..=600;
+
+const TICK_DURATION_REPORT_INTERVAL_TICKS: u32 = 600;
 
-const BLOCK_ROWS: usize = 5;
-const BLOCK_HITS_LIFE: usize = 1;
+const DEFAULT_MAX_SNAPSHOT_BYTES: usize = 64 * 1024;
+const MIN_MAX_SNAPSHOT_BYTES: usize = 1024;
 
-const BALL_SPEED: usize = 300;
+const MAX_INPUT_BUFFER_DEPTH: usize = 60;
 
-const PADDLE_SPEED: usize = 300;
+const DEFAULT_COUNTDOWN_SECONDS: u32 = 3;
+const MAX_COUNTDOWN_SECONDS: u32 = 10;
+
+const RECONNECT_GRACE_SECONDS_RANGE: RangeInclusive<u64> = 1..=600;
+
+const CONNECTION_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum AdminCommand {
+    Kick { player_id: u8, reason: String },
+    SaveState(PathBuf),
+    Breakthrough { player_id: u8 },
+}
 
-const GAME_LOOP_TIMESTEP_SECONDS: f32 = 1.0 / 60.0;
+enum GameLoopCommand {
+    SaveState(PathBuf),
+    Breakthrough { player_id: u8 },
+}
 
 struct PlayerKeyEvent {
     player_id: u8,
     key_code: u32,
 }
 
+#[derive(Clone)]
+struct ServerArguments {
+    block_layout: BlockLayout,
+    following_levels: Vec<BlockLayout>,
+    game_mode: GameMode,
+    match_settings: MatchSettings,
+    keep_alive_interval: Duration,
+    idle_timeout: Duration,
+    max_snapshot_bytes: usize,
+    full_snapshot_interval_ticks: u64,
+    dynamic_snapshots: bool,
+    input_buffer_depth: usize,
+    degrade_on_overload: bool,
+    countdown_seconds: u32,
+    reconnect_grace_seconds: Option<u16>,
+    simulated_latency: Duration,
+    simulated_drop_percent: u8,
+    required_players: u8,
+    load_state_path: Option<PathBuf>,
+    certificate_paths: Option<(PathBuf, PathBuf)>,
+}
+
+#[derive(PartialEq)]
+struct StaticState {
+    blocks: Vec<Block>,
+    layout_name: String,
+    level_index: Option<u8>,
+}
+
+impl StaticState {
+    fn from_world_data(world_data: &WorldData) -> Self {
+        StaticState {
+            blocks: world_data.blocks.clone(),
+            layout_name: world_data.layout_name.clone(),
+            level_index: world_data.level_index,
+        }
+    }
+}
+
+struct PlayerConnection {
+    receive_channel: Receiver<WorldData>,
+    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
+    server_info: ServerInfo,
+    max_snapshot_bytes: usize,
+    full_snapshot_interval_ticks: u64,
+    dynamic_snapshots: bool,
+    connected_players: Arc<AtomicU8>,
+    kick_receiver: Arc<AsyncMutex<mpsc::UnboundedReceiver<String>>>,
+    network_simulation: NetworkSimulation,
+}
+
+struct ConnectedPlayerGuard(Arc<AtomicU8>);
+
+impl ConnectedPlayerGuard {
+    fn new(connected_players: Arc<AtomicU8>) -> Self {
+        connected_players.fetch_add(1, Ordering::SeqCst);
+        ConnectedPlayerGuard(connected_players)
+    }
+}
+
+impl Drop for ConnectedPlayerGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct PlayerSlots {
+    is_taken: Mutex<Vec<bool>>,
+    slot_freed: Notify,
+}
+
+impl PlayerSlots {
+    fn new(player_capacity: u8) -> Arc<Self> {
+        Arc::new(PlayerSlots {
+            is_taken: Mutex::new(vec![false; player_capacity as usize]),
+            slot_freed: Notify::new(),
+        })
+    }
+
+    fn try_claim(self: &Arc<Self>) -> Option<PlayerSlotGuard> {
+        let mut is_taken = self.is_taken.lock().unwrap();
+        let player_id = is_taken.iter().position(|is_taken| !is_taken)?;
+        is_taken[player_id] = true;
+
+        Some(PlayerSlotGuard {
+            player_slots: self.clone(),
+            player_id: player_id as u8,
+        })
+    }
+
+    async fn claim(self: &Arc<Self>) -> PlayerSlotGuard {
+        loop {
+            if let Some(player_slot) = self.try_claim() {
+                return player_slot;
+            }
+
+            self.slot_freed.notified().await;
+        }
+    }
+}
+
+// Holds a player id for as long as its connection task runs, so a kicked or dropped player's
+// slot is handed to the next incoming session.
+struct PlayerSlotGuard {
+    player_slots: Arc<PlayerSlots>,
+    player_id: u8,
+}
+
+impl Drop for PlayerSlotGuard {
+    fn drop(&mut self) {
+        self.player_slots.is_taken.lock().unwrap()[self.player_id as usize] = false;
+        self.player_slots.slot_freed.notify_one();
+    }
+}
+
 #[tokio::main]
 async fn main() {
+    let server_arguments = match parse_server_arguments(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let identity = match load_identity(&server_arguments).await {
+        Ok(identity) => identity,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+
+    let saved_game_state = match &server_arguments.load_state_path {
+        Some(path) => match load_saved_game_state(path, server_arguments.game_mode) {
+            Ok(game_state) => Some(game_state),
+            Err(message) => {
+                eprintln!("{}", message);
+                std::process::exit(2);
+            }
+        },
+        None => None,
+    };
+
     let (world_data_send_channel, world_data_receive_channel) = mpsc::unbounded_channel();
 
     let (player_key_event_send_channel, player_key_event_receive_channel) =
         mpsc::unbounded_channel();
 
+    let server_arguments_for_server = server_arguments.clone();
+
+    let crash_replay = Arc::new(Mutex::new(CrashReplayBuffer::new(
+        CRASH_REPLAY_CAPACITY_TICKS,
+    )));
+    let crash_replay_for_game_loop = crash_replay.clone();
+
+    let connected_players = Arc::new(AtomicU8::new(0));
+    let connected_players_for_server = connected_players.clone();
+
+    let (player_0_kick_sender, player_0_kick_receiver) = mpsc::unbounded_channel();
+    let (player_1_kick_sender, player_1_kick_receiver) = mpsc::unbounded_channel();
+
+    let (game_loop_command_sender, game_loop_command_receiver) = mpsc::unbounded_channel();
+
+    spawn_admin_command_reader(
+        [player_0_kick_sender, player_1_kick_sender],
+        game_loop_command_sender,
+    );
+
     let game_loop_handle = tokio::spawn(async move {
-        start_game_loop(world_data_send_channel, player_key_event_receive_channel).await
+        start_game_loop(
+            server_arguments,
+            world_data_send_channel,
+            player_key_event_receive_channel,
+            crash_replay_for_game_loop,
+            connected_players,
+            saved_game_state,
+            game_loop_command_receiver,
+        )
+        .await
     });
 
     let server_handle = tokio::spawn(async move {
-        start_server(world_data_receive_channel, player_key_event_send_channel).await
+        start_server(
+            world_data_receive_channel,
+            player_key_event_send_channel,
+            server_arguments_for_server,
+            identity,
+            connected_players_for_server,
+            [player_0_kick_receiver, player_1_kick_receiver],
+        )
+        .await
     });
 
-    game_loop_handle.await.unwrap();
+    if let Err(e) = game_loop_handle.await {
+        if e.is_panic() {
+            dump_crash_replay(&crash_replay);
+        }
+
+        panic!("Game loop failed: {}", e);
+    }
     server_handle.await.unwrap();
 }
 
 async fn start_game_loop(
+    server_arguments: ServerArguments,
     world_data_send_channel: mpsc::UnboundedSender<WorldData>,
     mut player_key_event_receive_channel: mpsc::UnboundedReceiver<PlayerKeyEvent>,
+    crash_replay: Arc<Mutex<CrashReplayBuffer>>,
+    connected_players: Arc<AtomicU8>,
+    saved_game_state: Option<GameState>,
+    mut game_loop_command_receiver: mpsc::UnboundedReceiver<GameLoopCommand>,
 ) {
-    let mut world_data = create_world_data();
+    let ServerArguments {
+        block_layout,
+        following_levels,
+        game_mode,
+        match_settings,
+        input_buffer_depth,
+        degrade_on_overload,
+        countdown_seconds,
+        reconnect_grace_seconds,
+        required_players,
+        ..
+    } = server_arguments;
+
+    let is_resumed = saved_game_state.is_some();
+
+    let mut game_state = saved_game_state
+        .unwrap_or_else(|| game::create_world(block_layout, game_mode, match_settings));
+
+    let mut level_index = game_state
+        .world_data
+        .level_index
+        .map_or(0, |level_index| level_index as usize);
+
+    if is_resumed {
+        info!(
+            "Resuming saved match at tick {}, score {:?}",
+            game_state.world_data.tick, game_state.world_data.scores
+        );
+    } else if !following_levels.is_empty() {
+        game_state.world_data.level_index = Some(0);
+    }
+
+    let tick_budget = Duration::from_secs_f32(GAME_LOOP_TIMESTEP_SECONDS);
+    let mut reported_ticks: u32 = 0;
+    let mut reported_ticks_duration = Duration::ZERO;
+    let mut has_match_started = false;
+    let ticks_per_second = (1.0 / GAME_LOOP_TIMESTEP_SECONDS).round() as usize;
+    let countdown_ticks = countdown_seconds as usize * ticks_per_second;
+    let mut remaining_countdown_ticks: Option<usize> = None;
+    let mut has_countdown_started = false;
+    let mut remaining_grace_ticks: Option<usize> = None;
+    let mut input_buffer = (input_buffer_depth > 0).then(|| InputBuffer::new(input_buffer_depth));
+    let mut reported_dropped_inputs: u64 = 0;
+    let mut tick_watchdog = degrade_on_overload.then(|| TickWatchdog::new(tick_budget));
 
     loop {
-        let mut paddles: [Paddle; 2] = world_data.paddles.clone();
-        let mut balls: Vec<Ball> = world_data.balls.clone();
+        let tick_started_at = Instant::now();
+        let tick_span = debug_span!("game_tick").entered();
+
+        let mut tick_inputs: Vec<ReplayInput> = vec![];
+        let mut player_inputs: Vec<PlayerInput> = vec![];
+
+        let mut received_events: Vec<PlayerKeyEvent> = vec![];
 
         while let Ok(event) = player_key_event_receive_channel.try_recv() {
-            let index = paddles
-                .iter()
-                .position(|p| p.id == event.player_id)
-                .unwrap();
+            match input_buffer.as_mut() {
+                Some(input_buffer) => {
+                    let is_metered = matches!(
+                        player_action_from_key_code(event.key_code),
+                        Some(PlayerAction::MoveLeft | PlayerAction::MoveRight)
+                    );
+
+                    input_buffer.push(event, is_metered);
+                }
+                None => received_events.push(event),
+            }
+        }
 
-            let mut paddle_to_move = paddles[index].clone();
+        if let Some(input_buffer) = input_buffer.as_mut() {
+            received_events = input_buffer.take_tick_inputs();
 
-            if event.key_code == KeyboardKey::KEY_LEFT as u32 {
-                paddle_to_move.position.x -= PADDLE_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
+            if input_buffer.dropped_inputs() > reported_dropped_inputs {
+                debug!(
+                    "Input buffer full, dropped {} inputs so far",
+                    input_buffer.dropped_inputs()
+                );
+
+                reported_dropped_inputs = input_buffer.dropped_inputs();
             }
+        }
 
-            if event.key_code == KeyboardKey::KEY_RIGHT as u32 {
-                paddle_to_move.position.x += PADDLE_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
+        for event in received_events {
+            tick_inputs.push(ReplayInput {
+                player_id: event.player_id,
+                key_code: event.key_code,
+            });
+
+            if let Some(action) = player_action_from_key_code(event.key_code) {
+                player_inputs.push(PlayerInput {
+                    player_id: event.player_id,
+                    action,
+                });
             }
+        }
 
-            paddles[index] = paddle_to_move;
+        let connected = connected_players.load(Ordering::SeqCst);
 
-            if event.key_code == KeyboardKey::KEY_SPACE as u32 {
-                let ball_index = balls.iter().position(|p| p.id == event.player_id).unwrap();
-                let mut ball_to_move = balls[ball_index].clone();
+        if connected >= required_players {
+            if !has_match_started {
+                info!(
+                    "All {} players connected, starting the match",
+                    required_players
+                );
 
-                if !ball_to_move.is_free {
-                    ball_to_move.velocity = Vector2::new(0.0, -1.0);
-                    ball_to_move.is_free = true;
-                    balls[ball_index] = ball_to_move;
-                }
+                info!(
+                    "Match settings: mode {}, layout {}, seed {}, {:?}",
+                    game_state.game_mode.name(),
+                    game_state.world_data.layout_name,
+                    game_state.match_settings.random_seed,
+                    game_state.match_settings
+                );
+
+                has_match_started = true;
             }
-        }
 
-        for paddle in paddles.iter_mut() {
-            if paddle.position.x - PADDLE_WIDTH as f32 / 2.0 <= 0.0 {
-                paddle.position.x = PADDLE_WIDTH as f32 / 2.0;
+            if remaining_grace_ticks.take().is_some() {
+                info!("Players reconnected, resuming the match");
             }
 
-            if paddle.position.x + PADDLE_WIDTH as f32 / 2.0 >= WORLD_WIDTH as f32 {
-                paddle.position.x = WORLD_WIDTH as f32 - PADDLE_WIDTH as f32 / 2.0;
+            game_state.world_data.waiting_for_players = None;
+            game_state.world_data.reconnect_grace_seconds = None;
+        } else {
+            game_state.world_data.waiting_for_players = Some(required_players - connected);
+
+            if let Some(grace_seconds) = reconnect_grace_seconds.filter(|_| has_match_started) {
+                let remaining_ticks = remaining_grace_ticks.get_or_insert_with(|| {
+                    warn!(
+                        "A player disconnected, waiting {}s for them to reconnect",
+                        grace_seconds
+                    );
+
+                    grace_seconds as usize * ticks_per_second
+                });
+
+                *remaining_ticks -= 1;
+
+                game_state.world_data.reconnect_grace_seconds =
+                    Some(remaining_ticks.div_ceil(ticks_per_second) as u16);
+
+                if *remaining_ticks == 0 {
+                    warn!(
+                        "No player reconnected within {}s, abandoning the match",
+                        grace_seconds
+                    );
+
+                    let _ = world_data_send_channel.send(game_state.world_data.clone());
+
+                    return;
+                }
             }
         }
 
-        for ball in balls.iter_mut() {
-            if (ball.position.x < 0.0 || ball.position.x.abs_diff_eq(&0.0, f32::EPSILON))
-                || (ball.position.x + BALL_RADIUS as f32 > WORLD_WIDTH as f32
-                    || ball
-                        .position
-                        .x
-                        .abs_diff_eq(&(WORLD_WIDTH as f32), f32::EPSILON))
-            {
-                ball.velocity.x *= -1.0;
+        let is_everyone_ready = game_state
+            .world_data
+            .ready_players
+            .is_none_or(|ready_players| !ready_players.contains(&false));
+
+        if has_match_started && is_everyone_ready && !has_countdown_started {
+            has_countdown_started = true;
+
+            if countdown_ticks > 0 {
+                remaining_countdown_ticks = Some(countdown_ticks);
             }
         }
 
-        balls.retain(|b| {
-            (b.position.y <= 0.0) == false
-                && (b.position.y + BALL_RADIUS as f32 >= WORLD_HEIGHT as f32) == false
-        });
+        game_state.world_data.match_countdown =
+            remaining_countdown_ticks.map(|ticks| ticks.div_ceil(ticks_per_second) as u8);
 
-        for ball in balls.iter_mut() {
-            for paddle in &paddles {
-                if is_ball_collided_with_object(&ball, paddle.position, PADDLE_WIDTH, PADDLE_HEIGHT)
-                {
-                    let paddle_center = paddle.position.x;
-                    let ball_center = ball.position.x;
-                    let centers_difference = ball_center - paddle_center;
-
-                    if !centers_difference.abs_diff_eq(&0.0, f32::EPSILON) {
-                        let deflect_factor = centers_difference / (PADDLE_WIDTH as f32 / 2.0);
-                        ball.velocity.x = deflect_factor;
-                    }
+        if game_state.world_data.waiting_for_players.is_none() {
+            game::step_world(&mut game_state, &player_inputs);
 
-                    ball.velocity.y *= -1.0;
-                }
+            if !game_state.world_data.is_paused {
+                remaining_countdown_ticks = remaining_countdown_ticks
+                    .map(|ticks| ticks - 1)
+                    .filter(|ticks| *ticks > 0);
             }
         }
 
-        let mut blocks: Vec<Block> = world_data.blocks.clone();
+        if game_state.world_data.blocks.is_empty() && level_index < following_levels.len() {
+            let next_level = following_levels[level_index];
+            level_index += 1;
 
-        for ball in balls.iter_mut() {
-            for block in &mut blocks {
-                if is_ball_collided_with_object(&ball, block.position, BLOCK_SIZE, BLOCK_SIZE) {
-                    if is_ball_hit_top_or_bottom_of_block(&ball, &block) {
-                        ball.velocity.y *= -1.0;
-                    } else {
-                        ball.velocity.x *= -1.0;
-                    }
+            info!(
+                "Level {} cleared, loading level {} ({})",
+                level_index,
+                level_index + 1,
+                next_level.name()
+            );
+
+            game::load_level(&mut game_state, next_level);
+            game_state.world_data.level_index = Some(level_index as u8);
+        }
 
-                    block.hits_life -= 1;
+        while let Ok(command) = game_loop_command_receiver.try_recv() {
+            match command {
+                GameLoopCommand::SaveState(path) => {
+                    match saved_state::write_to_file(&game_state, &path) {
+                        Ok(()) => info!(
+                            "Saved match state at tick {} to {}",
+                            game_state.world_data.tick,
+                            path.display()
+                        ),
+                        Err(e) => {
+                            error!("Failed to save match state to {}: {}", path.display(), e)
+                        }
+                    }
+                }
+                GameLoopCommand::Breakthrough { player_id } => {
+                    if game_state.match_settings.breakthrough_seconds.is_none() {
+                        warn!("Breakthrough is disabled, start the server with --breakthrough-seconds");
+                        continue;
+                    }
 
-                    break;
+                    match game::grant_breakthrough(&mut game_state, player_id) {
+                        0 => warn!(
+                            "Player {} has no ball in play, nothing to break through",
+                            player_id
+                        ),
+                        balls => info!(
+                            "Granted breakthrough to {} ball(s) of player {}",
+                            balls, player_id
+                        ),
+                    }
                 }
             }
         }
 
-        blocks.retain(|b| b.hits_life != 0);
+        crash_replay
+            .lock()
+            .unwrap()
+            .record(tick_inputs, &game_state.world_data);
+
+        let is_broadcast_skipped = tick_watchdog.as_ref().is_some_and(|watchdog| {
+            watchdog.is_degraded()
+                && !game_state
+                    .world_data
+                    .tick
+                    .is_multiple_of(DEGRADED_BROADCAST_INTERVAL_TICKS)
+        });
 
-        for ball in balls.iter_mut() {
-            if ball.is_free {
-                ball.position += ball.velocity * BALL_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
-            }
+        if !is_broadcast_skipped {
+            trace_span!("broadcast").in_scope(|| {
+                world_data_send_channel
+                    .send(game_state.world_data.clone())
+                    .unwrap()
+            });
+        }
+
+        drop(tick_span);
+
+        let tick_duration = tick_started_at.elapsed();
+
+        if tick_duration > tick_budget {
+            warn!(
+                "Tick {} took {:?}, over the {:?} budget",
+                game_state.world_data.tick, tick_duration, tick_budget
+            );
+        }
+
+        match tick_watchdog
+            .as_mut()
+            .and_then(|watchdog| watchdog.record(tick_duration))
+        {
+            Some(WatchdogChange::Engaged) => warn!(
+                "Server is falling behind at tick {}, broadcasting every {} ticks",
+                game_state.world_data.tick, DEGRADED_BROADCAST_INTERVAL_TICKS
+            ),
+            Some(WatchdogChange::Disengaged) => info!(
+                "Server caught up at tick {}, broadcasting every tick again",
+                game_state.world_data.tick
+            ),
+            None => {}
         }
 
-        world_data.blocks = blocks;
-        world_data.paddles = paddles;
-        world_data.balls = balls;
+        reported_ticks += 1;
+        reported_ticks_duration += tick_duration;
+
+        if reported_ticks == TICK_DURATION_REPORT_INTERVAL_TICKS {
+            debug!(
+                "Average tick duration over the last {} ticks: {:?}",
+                reported_ticks,
+                reported_ticks_duration / reported_ticks
+            );
 
-        world_data_send_channel.send(world_data.clone()).unwrap();
+            reported_ticks = 0;
+            reported_ticks_duration = Duration::ZERO;
+        }
 
         tokio::time::sleep(Duration::from_secs_f32(GAME_LOOP_TIMESTEP_SECONDS)).await;
     }
 }
 
-fn create_world_data() -> WorldData {
-    let mut blocks: Vec<Block> = vec![];
+fn spawn_admin_command_reader(
+    kick_senders: [mpsc::UnboundedSender<String>; 2],
+    game_loop_command_sender: mpsc::UnboundedSender<GameLoopCommand>,
+) {
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let Ok(line) = line else {
+                break;
+            };
 
-    for row_index in 0..BLOCK_ROWS {
-        for block_index in 0..BLOCKS_IN_ROW {
-            blocks.push(Block {
-                position: Vector2::new(
-                    (block_index * (BLOCK_SIZE + 1)) as f32 + (BLOCK_SIZE as f32 / 2.0),
-                    (row_index * (BLOCK_SIZE + 1)) as f32
-                        + (BLOCK_SIZE as f32 / 2.0)
-                        + (WORLD_HEIGHT as f32 / 2.0)
-                        - (BLOCK_SIZE as f32 * 2.0 + BLOCK_SIZE as f32 / 2.0),
-                ),
-                hits_life: BLOCK_HITS_LIFE,
-            });
+            run_admin_command(&line, &kick_senders, &game_loop_command_sender);
+        }
+    });
+}
+
+fn run_admin_command(
+    line: &str,
+    kick_senders: &[mpsc::UnboundedSender<String>; 2],
+    game_loop_command_sender: &mpsc::UnboundedSender<GameLoopCommand>,
+) {
+    match parse_admin_command(line) {
+        Ok(None) => {}
+        Ok(Some(AdminCommand::Kick { player_id, reason })) => {
+            if kick_senders[player_id as usize].send(reason).is_err() {
+                warn!("Player {} is not connected, nothing to kick", player_id);
+            }
+        }
+        Ok(Some(AdminCommand::SaveState(path))) => {
+            if game_loop_command_sender
+                .send(GameLoopCommand::SaveState(path))
+                .is_err()
+            {
+                warn!("Game loop is not running, nothing to save");
+            }
         }
+        Ok(Some(AdminCommand::Breakthrough { player_id })) => {
+            if game_loop_command_sender
+                .send(GameLoopCommand::Breakthrough { player_id })
+                .is_err()
+            {
+                warn!("Game loop is not running, no ball to break through");
+            }
+        }
+        Err(message) => warn!("{}", message),
     }
+}
 
-    let paddles: [Paddle; 2] = [
-        Paddle {
-            id: 1,
-            position: Vector2::new(WORLD_WIDTH as f32 / 2.0, PADDLE_HEIGHT as f32),
-        },
-        Paddle {
-            id: 0,
-            position: Vector2::new(
-                WORLD_WIDTH as f32 / 2.0,
-                WORLD_HEIGHT as f32 - PADDLE_HEIGHT as f32,
-            ),
-        },
-    ];
-
-    let balls: Vec<Ball> = Vec::from([
-        Ball {
-            id: 1,
-            position: Vector2::new(
-                paddles[0].position.x,
-                paddles[0].position.y + PADDLE_HEIGHT as f32 / 2.0 + BALL_RADIUS as f32,
-            ),
-            velocity: Vector2::new(0.0, 0.0),
-            is_free: false,
-        },
-        Ball {
-            id: 0,
-            position: Vector2::new(
-                paddles[1].position.x,
-                paddles[1].position.y - PADDLE_HEIGHT as f32 / 2.0 - BALL_RADIUS as f32,
-            ),
-            velocity: Vector2::new(0.0, 0.0),
-            is_free: false,
-        },
-    ]);
+fn parse_admin_command(line: &str) -> Result<Option<AdminCommand>, String> {
+    let mut words = line.split_whitespace();
+
+    match words.next() {
+        None => Ok(None),
+        Some("kick") => parse_kick_command(words).map(Some),
+        Some("save-state") => {
+            let path = words.next().ok_or("save-state requires a file path")?;
 
-    WorldData {
-        blocks,
-        paddles,
-        balls,
+            Ok(Some(AdminCommand::SaveState(PathBuf::from(path))))
+        }
+        Some("breakthrough") => {
+            let player_id = parse_player_id("breakthrough", words.next())?;
+
+            Ok(Some(AdminCommand::Breakthrough { player_id }))
+        }
+        Some(command) => Err(format!("Unknown admin command '{}'", command)),
     }
 }
 
-async fn start_server(
-    mut receive_channel: mpsc::UnboundedReceiver<WorldData>,
-    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
-) {
-    init_logging();
+fn parse_kick_command<'a>(
+    mut words: impl Iterator<Item = &'a str>,
+) -> Result<AdminCommand, String> {
+    let player_id = parse_player_id("kick", words.next())?;
 
-    let config = ServerConfig::builder()
-        .with_bind_default(4433)
-        .with_identity(&Identity::self_signed(&["localhost", "127.0.0.1", "::1"]).unwrap())
-        .keep_alive_interval(Some(Duration::from_secs(3)))
-        .build();
+    let reason = words.collect::<Vec<_>>().join(" ");
+    let reason = if reason.is_empty() {
+        "removed by the server admin".to_string()
+    } else {
+        reason
+    };
 
-    let server = Endpoint::server(config).unwrap();
+    Ok(AdminCommand::Kick { player_id, reason })
+}
 
-    info!("Server ready!");
+fn parse_player_id(command: &str, value: Option<&str>) -> Result<u8, String> {
+    let value = value.ok_or_else(|| format!("{} requires a player id (0 or 1)", command))?;
 
-    let initial_world_data = receive_channel.recv().await.unwrap();
-    let (player_1_sender, player_1_receiver) = channel(initial_world_data.clone());
-    let (player_2_sender, player_2_receiver) = channel(initial_world_data);
+    value
+        .parse()
+        .ok()
+        .filter(|player_id| *player_id <= 1)
+        .ok_or_else(|| format!("{} expects a player id of 0 or 1, got '{}'", command, value))
+}
 
-    tokio::spawn(async move {
-        while let Some(data) = receive_channel.recv().await {
-            let _ = player_1_sender.send(data.clone());
-            let _ = player_2_sender.send(data);
-        }
-    });
+fn load_saved_game_state(path: &Path, game_mode: GameMode) -> Result<GameState, String> {
+    let game_state = saved_state::read_from_file(path)
+        .map_err(|e| format!("Failed to load match state from {}: {}", path.display(), e))?;
 
-    let incoming_session = server.accept().await;
+    if game_state.game_mode != game_mode {
+        return Err(format!(
+            "Saved match was played in {} mode, start the server with --mode {}",
+            game_state.game_mode.name(),
+            game_state.game_mode.name()
+        ));
+    }
 
-    tokio::spawn(
-        handle_connection(
-            incoming_session,
-            player_1_receiver,
-            0,
-            player_key_event_send_channel.clone(),
-        )
-        .instrument(info_span!("Player 0 connected!.")),
-    );
+    game_state
+        .match_settings
+        .validate()
+        .map_err(|message| format!("Saved match has invalid settings: {}", message))?;
 
-    let incoming_session = server.accept().await;
+    Ok(game_state)
+}
 
-    tokio::spawn(
-        handle_connection(
-            incoming_session,
-            player_2_receiver,
-            1,
-            player_key_event_send_channel,
-        )
-        .instrument(info_span!("Player 1 connected!.")),
-    );
+fn player_action_from_key_code(key_code: u32) -> Option<PlayerAction> {
+    match key_code {
+        code if code == KeyboardKey::KEY_LEFT as u32 => Some(PlayerAction::MoveLeft),
+        code if code == KeyboardKey::KEY_RIGHT as u32 => Some(PlayerAction::MoveRight),
+        code if code == KeyboardKey::KEY_SPACE as u32 => Some(PlayerAction::Launch),
+        code if code == KeyboardKey::KEY_P as u32 => Some(PlayerAction::TogglePause),
+        code if code == KeyboardKey::KEY_R as u32 => Some(PlayerAction::Ready),
+        _ => None,
+    }
 }
 
-fn init_logging() {
-    let env_filter = EnvFilter::builder()
-        .with_default_directive(LevelFilter::INFO.into())
-        .from_env_lossy();
+fn dump_crash_replay(crash_replay: &Mutex<CrashReplayBuffer>) {
+    let crash_replay = crash_replay.lock().unwrap_or_else(|e| e.into_inner());
 
-    tracing_subscriber::fmt()
-        .with_target(true)
-        .with_level(true)
-        .with_env_filter(env_filter)
-        .init();
+    match crash_replay.write_to_file(Path::new(CRASH_REPLAY_PATH)) {
+        Ok(frames_count) => error!(
+            "Game loop panicked, wrote the last {} ticks to {}",
+            frames_count, CRASH_REPLAY_PATH
+        ),
+        Err(e) => error!("Game loop panicked, failed to write crash replay: {}", e),
+    }
 }
 
-async fn handle_connection(
-    incoming_session: IncomingSession,
-    receive_channel: Receiver<WorldData>,
-    player_id: u8,
-    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
-) {
-    let result = handle_connection_impl(
-        incoming_session,
-        receive_channel,
-        player_id,
-        player_key_event_send_channel,
-    )
-    .await;
-    error!("{:?}", result);
-}
+fn parse_server_arguments(
+    mut arguments: impl Iterator<Item = String>,
+) -> Result<ServerArguments, String> {
+    let mut server_arguments = ServerArguments {
+        block_layout: BlockLayout::Grid,
+        following_levels: vec![],
+        game_mode: GameMode::Versus,
+        match_settings: MatchSettings::default(),
+        keep_alive_interval: Duration::from_secs(DEFAULT_KEEP_ALIVE_INTERVAL_SECONDS),
+        idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECONDS),
+        max_snapshot_bytes: DEFAULT_MAX_SNAPSHOT_BYTES,
+        full_snapshot_interval_ticks: 1,
+        dynamic_snapshots: false,
+        input_buffer_depth: 0,
+        degrade_on_overload: false,
+        countdown_seconds: DEFAULT_COUNTDOWN_SECONDS,
+        reconnect_grace_seconds: None,
+        simulated_latency: Duration::ZERO,
+        simulated_drop_percent: 0,
+        required_players: 0,
+        load_state_path: None,
+        certificate_paths: None,
+    };
+
+    let mut required_players: Option<u8> = None;
+    let mut certificate_path: Option<PathBuf> = None;
+    let mut private_key_path: Option<PathBuf> = None;
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--layout" => {
+                let name = arguments
+                    .next()
+                    .ok_or("--layout requires a value (grid, pyramid, checkerboard, fortress)")?;
+
+                server_arguments.block_layout = BlockLayout::from_name(&name)
+                    .ok_or_else(|| format!("Unknown block layout '{}'", name))?;
+            }
+            "--levels" => {
+                let names = arguments
+                    .next()
+                    .ok_or("--levels requires a comma-separated list of block layouts")?;
+
+                let mut levels = names
+                    .split(',')
+                    .map(|name| {
+                        BlockLayout::from_name(name)
+                            .ok_or_else(|| format!("Unknown block layout '{}'", name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if levels.len() > u8::MAX as usize {
+                    return Err(format!(
+                        "--levels accepts at most {} levels, got {}",
+                        u8::MAX,
+                        levels.len()
+                    ));
+                }
 
-async fn handle_connection_impl(
-    incoming_session: IncomingSession,
-    mut receive_channel: Receiver<WorldData>,
-    player_id: u8,
-    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
-) -> Result<(), Box<dyn Error>> {
-    info!("Waiting for session request...");
+                server_arguments.block_layout = levels.remove(0);
+                server_arguments.following_levels = levels;
+            }
+            "--mode" => {
+                let name = arguments
+                    .next()
+                    .ok_or("--mode requires a value (versus, practice, shared)")?;
+
+                server_arguments.game_mode =
+                    GameMode::ALL
+                        .into_iter()
+                        .find(|m| m.name() == name)
+                        .ok_or_else(|| format!("Unknown game mode '{}'", name))?;
+            }
+            "--gravity" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--gravity requires a value in world units per second squared")?;
+
+                let gravity: f32 = value
+                    .parse()
+                    .map_err(|_| format!("--gravity expects a number, got '{}'", value))?;
+
+                if !gravity.is_finite() || gravity < 0.0 {
+                    return Err(format!(
+                        "--gravity must be a non-negative number, got {}",
+                        gravity
+                    ));
+                }
 
-    let session_request = incoming_session.await?;
+                server_arguments.match_settings.gravity = gravity;
+            }
+            "--ball-radius" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--ball-radius requires a value in world units")?;
+
+                let ball_radius: f32 = value
+                    .parse()
+                    .map_err(|_| format!("--ball-radius expects a number, got '{}'", value))?;
+
+                if !ball_radius.is_finite() || ball_radius <= 0.0 {
+                    return Err(format!(
+                        "--ball-radius must be a positive number, got {}",
+                        ball_radius
+                    ));
+                }
 
-    info!(
-        "New session: Authority: '{}', Path: '{}'",
-        session_request.authority(),
-        session_request.path()
-    );
+                server_arguments.match_settings.ball_radius = ball_radius;
+            }
+            "--player-0-paddle-offset" => {
+                server_arguments.match_settings.paddle_offsets[0] =
+                    parse_paddle_offset_argument(&argument, arguments.next())?;
+            }
+            "--player-1-paddle-offset" => {
+                server_arguments.match_settings.paddle_offsets[1] =
+                    parse_paddle_offset_argument(&argument, arguments.next())?;
+            }
+            "--deflection-curve" => {
+                let name = arguments
+                    .next()
+                    .ok_or("--deflection-curve requires a value (linear, quadratic, clamped)")?;
+
+                server_arguments.match_settings.deflection_curve = DeflectionCurve::ALL
+                    .into_iter()
+                    .find(|c| c.name() == name)
+                    .ok_or_else(|| format!("Unknown deflection curve '{}'", name))?;
+            }
+            "--goal-line" => {
+                let name = arguments
+                    .next()
+                    .ok_or("--goal-line requires a value (flat, notch)")?;
+
+                server_arguments.match_settings.goal_line = GoalLine::ALL
+                    .into_iter()
+                    .find(|g| g.name() == name)
+                    .ok_or_else(|| format!("Unknown goal line '{}'", name))?;
+            }
+            "--paddle-side-hit" => {
+                let name = arguments
+                    .next()
+                    .ok_or("--paddle-side-hit requires a value (reflect, pass-through)")?;
+
+                server_arguments.match_settings.paddle_side_hit = PaddleSideHit::ALL
+                    .into_iter()
+                    .find(|h| h.name() == name)
+                    .ok_or_else(|| format!("Unknown paddle side hit behavior '{}'", name))?;
+            }
+            "--launch-direction" => {
+                let name = arguments.next().ok_or(
+                    "--launch-direction requires a value (straight, cone, toward-conceder)",
+                )?;
+
+                server_arguments.match_settings.launch_direction = LaunchDirection::ALL
+                    .into_iter()
+                    .find(|d| d.name() == name)
+                    .ok_or_else(|| format!("Unknown launch direction '{}'", name))?;
+            }
+            "--require-ready" => server_arguments.match_settings.require_ready = true,
+            "--dynamic-snapshots" => server_arguments.dynamic_snapshots = true,
+            "--degrade-on-overload" => server_arguments.degrade_on_overload = true,
+            "--cert" => {
+                let path = arguments
+                    .next()
+                    .ok_or("--cert requires a path to a PEM certificate chain")?;
+
+                certificate_path = Some(parse_existing_file_path(&argument, path)?);
+            }
+            "--key" => {
+                let path = arguments
+                    .next()
+                    .ok_or("--key requires a path to a PEM private key")?;
 
-    let connection = session_request.accept().await?;
+                private_key_path = Some(parse_existing_file_path(&argument, path)?);
+            }
+            "--load-state" => {
+                let path = arguments
+                    .next()
+                    .ok_or("--load-state requires a path to a saved match")?;
 
-    let (mut send_stream, mut receive_stream) = connection.accept_bi().await?;
-    send_stream.write_u8(player_id).await?;
-    send_stream.flush().await?;
+                server_arguments.load_state_path = Some(PathBuf::from(path));
+            }
+            "--training-ball" => server_arguments.match_settings.training_ball = true,
+            "--paddle-block-collision" => {
+                server_arguments.match_settings.paddle_block_collision = true
+            }
+            "--block-regeneration-seconds" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--block-regeneration-seconds requires a number of seconds")?;
+
+                let block_regeneration_seconds: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--block-regeneration-seconds expects a whole number of seconds, got '{}'",
+                        value
+                    )
+                })?;
+
+                if block_regeneration_seconds == 0 {
+                    return Err("--block-regeneration-seconds must be greater than 0".to_string());
+                }
 
-    loop {
-        tokio::select! {
-            player_key_sygnal = receive_stream.read_u32() => {
-                player_key_event_send_channel.send(PlayerKeyEvent{player_id, key_code: player_key_sygnal?})?;
+                server_arguments.match_settings.block_regeneration_seconds =
+                    Some(block_regeneration_seconds);
             }
-            _ = receive_channel.changed() => {
-                let world_data = receive_channel.borrow().clone();
-                let buf = rmp_serde::to_vec(&world_data)?;
-                let len = buf.len() as u32;
-                send_stream.write_u32(len).await?;
-                send_stream.write_all(&buf).await?;
-                send_stream.flush().await?;
+            "--breakthrough-seconds" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--breakthrough-seconds requires a number of seconds")?;
+
+                let breakthrough_seconds: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--breakthrough-seconds expects a whole number of seconds, got '{}'",
+                        value
+                    )
+                })?;
+
+                if breakthrough_seconds == 0 {
+                    return Err("--breakthrough-seconds must be greater than 0".to_string());
+                }
+
+                server_arguments.match_settings.breakthrough_seconds = Some(breakthrough_seconds);
             }
-        }
-    }
-}
+            "--paddle-speed" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--paddle-speed requires a value in world units per second")?;
+
+                let paddle_speed: f32 = value
+                    .parse()
+                    .map_err(|_| format!("--paddle-speed expects a number, got '{}'", value))?;
+
+                if !paddle_speed.is_finite() || paddle_speed <= 0.0 {
+                    return Err(format!(
+                        "--paddle-speed must be a positive number, got {}",
+                        paddle_speed
+                    ));
+                }
 
-fn is_ball_collided_with_object(
-    ball: &Ball,
-    position: Vector2<f32>,
-    width: usize,
-    height: usize,
-) -> bool {
-    let ball_left = ball.position.x - BALL_RADIUS as f32;
-    let ball_right = ball.position.x + BALL_RADIUS as f32;
-    let ball_top = ball.position.y - BALL_RADIUS as f32;
-    let ball_bottom = ball.position.y + BALL_RADIUS as f32;
+                server_arguments.match_settings.paddle_speed = paddle_speed;
+            }
+            "--paddle-acceleration" => {
+                let value = arguments.next().ok_or(
+                    "--paddle-acceleration requires a value in world units per second squared",
+                )?;
+
+                let paddle_acceleration: f32 = value.parse().map_err(|_| {
+                    format!("--paddle-acceleration expects a number, got '{}'", value)
+                })?;
+
+                if !paddle_acceleration.is_finite() || paddle_acceleration <= 0.0 {
+                    return Err(format!(
+                        "--paddle-acceleration must be a positive number, got {}",
+                        paddle_acceleration
+                    ));
+                }
 
-    let object_left = position.x - (width as f32 / 2.0);
-    let object_right = position.x + (width as f32 / 2.0);
-    let object_top = position.y - (height as f32 / 2.0);
-    let object_bottom = position.y + (height as f32 / 2.0);
+                server_arguments.match_settings.paddle_acceleration = Some(paddle_acceleration);
+            }
+            "--mercy-gap" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--mercy-gap requires a score difference")?;
+
+                let mercy_score_gap: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--mercy-gap expects a whole number of points, got '{}'",
+                        value
+                    )
+                })?;
+
+                server_arguments.match_settings.mercy_score_gap = Some(mercy_score_gap);
+            }
+            "--max-rally-seconds" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--max-rally-seconds requires a number of seconds")?;
+
+                let max_rally_seconds: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--max-rally-seconds expects a whole number of seconds, got '{}'",
+                        value
+                    )
+                })?;
+
+                if max_rally_seconds == 0 {
+                    return Err("--max-rally-seconds must be greater than 0".to_string());
+                }
 
-    ball_left < object_right
-        && ball_right > object_left
-        && ball_top < object_bottom
-        && ball_bottom > object_top
-}
+                server_arguments.match_settings.max_rally_seconds = Some(max_rally_seconds);
+            }
+            "--balls-per-player" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--balls-per-player requires a number of balls")?;
+
+                let balls_per_player: u8 = value.parse().map_err(|_| {
+                    format!("--balls-per-player expects a whole number, got '{}'", value)
+                })?;
+
+                if !(1..=MAX_BALLS_PER_PLAYER).contains(&balls_per_player) {
+                    return Err(format!(
+                        "--balls-per-player must be between 1 and {}, got {}",
+                        MAX_BALLS_PER_PLAYER, balls_per_player
+                    ));
+                }
+
+                server_arguments.match_settings.balls_per_player = balls_per_player;
+            }
+            "--collision-margin" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--collision-margin requires a value in world units")?;
+
+                let collision_margin: f32 = value
+                    .parse()
+                    .map_err(|_| format!("--collision-margin expects a number, got '{}'", value))?;
+
+                if !collision_margin.is_finite() {
+                    return Err(format!(
+                        "--collision-margin must be a finite number, got {}",
+                        collision_margin
+                    ));
+                }
+
+                server_arguments.match_settings.collision_margin = collision_margin;
+            }
+            "--arena-edge-dead-zone" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--arena-edge-dead-zone requires a value in world units")?;
+
+                let arena_edge_dead_zone: f32 = value.parse().map_err(|_| {
+                    format!("--arena-edge-dead-zone expects a number, got '{}'", value)
+                })?;
+
+                if !(0.0..=MAX_ARENA_EDGE_DEAD_ZONE).contains(&arena_edge_dead_zone) {
+                    return Err(format!(
+                        "--arena-edge-dead-zone must be between 0 and {}, got {}",
+                        MAX_ARENA_EDGE_DEAD_ZONE, arena_edge_dead_zone
+                    ));
+                }
 
-fn is_ball_hit_top_or_bottom_of_block(ball: &Ball, block: &Block) -> bool {
-    let vector_from_block_to_ball = ball.position - block.position;
+                server_arguments.match_settings.arena_edge_dead_zone = arena_edge_dead_zone;
+            }
+            "--bounce-jitter" => {
+                let value = arguments.next().ok_or("--bounce-jitter requires a value")?;
+
+                let bounce_jitter: f32 = value
+                    .parse()
+                    .map_err(|_| format!("--bounce-jitter expects a number, got '{}'", value))?;
+
+                if !(0.0..=MAX_BALL_X_VELOCITY).contains(&bounce_jitter) {
+                    return Err(format!(
+                        "--bounce-jitter must be between 0 and {}, got {}",
+                        MAX_BALL_X_VELOCITY, bounce_jitter
+                    ));
+                }
+
+                server_arguments.match_settings.bounce_jitter = bounce_jitter;
+            }
+            "--split-paddle-gap" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--split-paddle-gap requires a value in world units")?;
+
+                let split_paddle_gap: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--split-paddle-gap expects a whole number of world units, got '{}'",
+                        value
+                    )
+                })?;
+
+                if !(1..=MAX_SPLIT_PADDLE_GAP).contains(&split_paddle_gap) {
+                    return Err(format!(
+                        "--split-paddle-gap must be between 1 and {}, got {}",
+                        MAX_SPLIT_PADDLE_GAP, split_paddle_gap
+                    ));
+                }
+
+                server_arguments.match_settings.split_paddle_gap = Some(split_paddle_gap);
+            }
+            "--seed" => {
+                let value = arguments.next().ok_or("--seed requires a value")?;
 
-    vector_from_block_to_ball.y.abs() > vector_from_block_to_ball.x.abs()
+                server_arguments.match_settings.random_seed = value
+                    .parse()
+                    .map_err(|_| format!("--seed expects a whole number, got '{}'", value))?;
+            }
+            "--keep-alive-seconds" => {
+                server_arguments.keep_alive_interval = Duration::from_secs(parse_seconds_argument(
+                    &argument,
+                    arguments.next(),
+                    KEEP_ALIVE_INTERVAL_SECONDS_RANGE,
+                )?);
+            }
+            "--idle-timeout-seconds" => {
+                server_arguments.idle_timeout = Duration::from_secs(parse_seconds_argument(
+                    &argument,
+                    arguments.next(),
+                    IDLE_TIMEOUT_SECONDS_RANGE,
+                )?);
+            }
+            "--players" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--players requires a number of players")?;
+
+                required_players =
+                    Some(value.parse().map_err(|_| {
+                        format!("--players expects a whole number, got '{}'", value)
+                    })?);
+            }
+            "--max-snapshot-bytes" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--max-snapshot-bytes requires a number of bytes")?;
+
+                let max_snapshot_bytes: usize = value.parse().map_err(|_| {
+                    format!(
+                        "--max-snapshot-bytes expects a whole number of bytes, got '{}'",
+                        value
+                    )
+                })?;
+
+                if max_snapshot_bytes < MIN_MAX_SNAPSHOT_BYTES {
+                    return Err(format!(
+                        "--max-snapshot-bytes must be at least {}, got {}",
+                        MIN_MAX_SNAPSHOT_BYTES, max_snapshot_bytes
+                    ));
+                }
+
+                server_arguments.max_snapshot_bytes = max_snapshot_bytes;
+            }
+            "--full-snapshot-interval-ticks" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--full-snapshot-interval-ticks requires a number of ticks")?;
+
+                let full_snapshot_interval_ticks: u64 = value.parse().map_err(|_| {
+                    format!(
+                        "--full-snapshot-interval-ticks expects a whole number of ticks, got '{}'",
+                        value
+                    )
+                })?;
+
+                if full_snapshot_interval_ticks == 0 {
+                    return Err("--full-snapshot-interval-ticks must be at least 1".to_string());
+                }
+
+                server_arguments.full_snapshot_interval_ticks = full_snapshot_interval_ticks;
+            }
+            "--debug-simulated-latency-ms" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--debug-simulated-latency-ms requires a number of milliseconds")?;
+
+                let latency_ms: u64 = value.parse().map_err(|_| {
+                    format!(
+                        "--debug-simulated-latency-ms expects a whole number of milliseconds, got '{}'",
+                        value
+                    )
+                })?;
+
+                if latency_ms > MAX_SIMULATED_LATENCY_MS {
+                    return Err(format!(
+                        "--debug-simulated-latency-ms must be at most {}, got {}",
+                        MAX_SIMULATED_LATENCY_MS, latency_ms
+                    ));
+                }
+
+                server_arguments.simulated_latency = Duration::from_millis(latency_ms);
+            }
+            "--debug-simulated-drop-percent" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--debug-simulated-drop-percent requires a percentage")?;
+
+                let drop_percent: u8 = value
+                    .parse()
+                    .ok()
+                    .filter(|percent| *percent <= MAX_SIMULATED_DROP_PERCENT)
+                    .ok_or_else(|| {
+                        format!(
+                            "--debug-simulated-drop-percent must be between 0 and {}, got '{}'",
+                            MAX_SIMULATED_DROP_PERCENT, value
+                        )
+                    })?;
+
+                server_arguments.simulated_drop_percent = drop_percent;
+            }
+            "--reconnect-grace-seconds" => {
+                let reconnect_grace_seconds = parse_seconds_argument(
+                    &argument,
+                    arguments.next(),
+                    RECONNECT_GRACE_SECONDS_RANGE,
+                )?;
+
+                server_arguments.reconnect_grace_seconds = Some(reconnect_grace_seconds as u16);
+            }
+            "--countdown-seconds" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--countdown-seconds requires a number of seconds")?;
+
+                let countdown_seconds: u32 = value.parse().map_err(|_| {
+                    format!(
+                        "--countdown-seconds expects a whole number of seconds, got '{}'",
+                        value
+                    )
+                })?;
+
+                if countdown_seconds > MAX_COUNTDOWN_SECONDS {
+                    return Err(format!(
+                        "--countdown-seconds must be at most {}, got {}",
+                        MAX_COUNTDOWN_SECONDS, countdown_seconds
+                    ));
+                }
+
+                server_arguments.countdown_seconds = countdown_seconds;
+            }
+            "--input-buffer-depth" => {
+                let value = arguments
+                    .next()
+                    .ok_or("--input-buffer-depth requires a number of inputs")?;
+
+                let input_buffer_depth: usize = value.parse().map_err(|_| {
+                    format!(
+                        "--input-buffer-depth expects a whole number of inputs, got '{}'",
+                        value
+                    )
+                })?;
+
+                if input_buffer_depth > MAX_INPUT_BUFFER_DEPTH {
+                    return Err(format!(
+                        "--input-buffer-depth must be at most {}, got {}",
+                        MAX_INPUT_BUFFER_DEPTH, input_buffer_depth
+                    ));
+                }
+
+                server_arguments.input_buffer_depth = input_buffer_depth;
+            }
+            _ => return Err(format!("Unknown argument '{}'", argument)),
+        }
+    }
+
+    if server_arguments.keep_alive_interval >= server_arguments.idle_timeout {
+        return Err(format!(
+            "Keep-alive interval ({}s) must be shorter than the idle timeout ({}s)",
+            server_arguments.keep_alive_interval.as_secs(),
+            server_arguments.idle_timeout.as_secs()
+        ));
+    }
+
+    server_arguments.match_settings.validate()?;
+
+    server_arguments.certificate_paths = match (certificate_path, private_key_path) {
+        (Some(certificate_path), Some(private_key_path)) => {
+            Some((certificate_path, private_key_path))
+        }
+        (None, None) => None,
+        _ => return Err("--cert and --key must be given together".to_string()),
+    };
+
+    let player_capacity = server_arguments.game_mode.player_capacity();
+
+    server_arguments.required_players = required_players.unwrap_or(player_capacity);
+
+    if !(1..=player_capacity).contains(&server_arguments.required_players) {
+        return Err(format!(
+            "--players must be between 1 and {} in {} mode, got {}",
+            player_capacity,
+            server_arguments.game_mode.name(),
+            server_arguments.required_players
+        ));
+    }
+
+    Ok(server_arguments)
+}
+
+fn parse_existing_file_path(name: &str, value: String) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+
+    if !path.is_file() {
+        return Err(format!("{} file '{}' does not exist", name, path.display()));
+    }
+
+    Ok(path)
+}
+
+async fn load_identity(server_arguments: &ServerArguments) -> Result<Identity, String> {
+    match &server_arguments.certificate_paths {
+        Some((certificate_path, private_key_path)) => {
+            Identity::load_pemfiles(certificate_path, private_key_path)
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Failed to load the certificate '{}' and key '{}': {}",
+                        certificate_path.display(),
+                        private_key_path.display(),
+                        e
+                    )
+                })
+        }
+        None => Identity::self_signed(["localhost", "127.0.0.1", "::1"])
+            .map_err(|e| format!("Failed to generate a self-signed certificate: {}", e)),
+    }
+}
+
+fn parse_seconds_argument(
+    name: &str,
+    value: Option<String>,
+    range: RangeInclusive<u64>,
+) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{} requires a number of seconds", name))?;
+
+    let seconds: u64 = value.parse().map_err(|_| {
+        format!(
+            "{} expects a whole number of seconds, got '{}'",
+            name, value
+        )
+    })?;
+
+    if !range.contains(&seconds) {
+        return Err(format!(
+            "{} must be between {} and {} seconds, got {}",
+            name,
+            range.start(),
+            range.end(),
+            seconds
+        ));
+    }
+
+    Ok(seconds)
+}
+
+fn parse_paddle_offset_argument(name: &str, value: Option<String>) -> Result<f32, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value in world units", name))?;
+
+    let offset: f32 = value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got '{}'", name, value))?;
+
+    let min_offset = PADDLE_HEIGHT as f32 / 2.0;
+    let max_offset = WORLD_HEIGHT as f32 / 2.0 - PADDLE_HEIGHT as f32;
+
+    if !(min_offset..=max_offset).contains(&offset) {
+        return Err(format!(
+            "{} must be between {} and {}, got {}",
+            name, min_offset, max_offset, offset
+        ));
+    }
+
+    Ok(offset)
+}
+
+async fn start_server(
+    receive_channel: mpsc::UnboundedReceiver<WorldData>,
+    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
+    server_arguments: ServerArguments,
+    identity: Identity,
+    connected_players: Arc<AtomicU8>,
+    kick_receivers: [mpsc::UnboundedReceiver<String>; 2],
+) {
+    init_logging();
+
+    if let Some(certificate) = identity.certificate_chain().as_slice().first() {
+        info!(
+            "Certificate SHA-256 (for the client's --server-cert-hash): {}",
+            certificate.hash().fmt(Sha256DigestFmt::DottedHex)
+        );
+    }
+
+    let config = ServerConfig::builder()
+        .with_bind_default(4433)
+        .with_identity(&identity)
+        .keep_alive_interval(Some(server_arguments.keep_alive_interval))
+        .max_idle_timeout(Some(server_arguments.idle_timeout))
+        .unwrap()
+        .build();
+
+    info!(
+        "Keep-alive interval: {}s, idle timeout: {}s",
+        server_arguments.keep_alive_interval.as_secs(),
+        server_arguments.idle_timeout.as_secs()
+    );
+
+    let server = Endpoint::server(config).unwrap();
+
+    let server_info = create_server_info(&server_arguments);
+
+    if !server_arguments.simulated_latency.is_zero() || server_arguments.simulated_drop_percent > 0
+    {
+        warn!(
+            "Debug network simulation is on: {:?} latency, {}% of snapshots dropped",
+            server_arguments.simulated_latency, server_arguments.simulated_drop_percent
+        );
+    }
+
+    info!("Server ready!");
+
+    let Some([player_1_receiver, player_2_receiver]) =
+        spawn_world_data_broadcast(receive_channel).await
+    else {
+        error!("Game loop stopped before sending the initial world, shutting the server down");
+        return;
+    };
+    let world_data_receivers = [player_1_receiver, player_2_receiver];
+    let kick_receivers = kick_receivers.map(|receiver| Arc::new(AsyncMutex::new(receiver)));
+    let player_slots = PlayerSlots::new(server_arguments.game_mode.player_capacity());
+    let mut connection_tasks = JoinSet::new();
+
+    loop {
+        let (player_slot, incoming_session) = tokio::select! {
+            accepted = accept_into_free_slot(&server, &player_slots) => accepted,
+            _ = wait_for_world_updates_to_stop(world_data_receivers[0].clone()) => break,
+        };
+
+        let player_id = player_slot.player_id;
+
+        while connection_tasks.try_join_next().is_some() {}
+
+        let connection = PlayerConnection {
+            receive_channel: world_data_receivers[player_id as usize].clone(),
+            player_key_event_send_channel: player_key_event_send_channel.clone(),
+            server_info: server_info.clone(),
+            max_snapshot_bytes: server_arguments.max_snapshot_bytes,
+            full_snapshot_interval_ticks: server_arguments.full_snapshot_interval_ticks,
+            dynamic_snapshots: server_arguments.dynamic_snapshots,
+            connected_players: connected_players.clone(),
+            kick_receiver: kick_receivers[player_id as usize].clone(),
+            network_simulation: create_network_simulation(&server_arguments, player_id),
+        };
+
+        connection_tasks.spawn(
+            handle_connection(incoming_session, player_slot, connection)
+                .instrument(info_span!("Player connected!.", player_id)),
+        );
+    }
+
+    // The connections still have to tell their players why the match ended, so the process must
+    // not exit before they have sent their disconnect message.
+    let all_connections_closed = async { while connection_tasks.join_next().await.is_some() {} };
+
+    if tokio::time::timeout(CONNECTION_SHUTDOWN_TIMEOUT, all_connections_closed)
+        .await
+        .is_err()
+    {
+        warn!(
+            "Connections did not close within {}s, shutting down anyway",
+            CONNECTION_SHUTDOWN_TIMEOUT.as_secs()
+        );
+    }
+}
+
+// A session is only accepted once a slot is free, so clients connecting to a full server wait
+// in the endpoint's queue until a player leaves or is kicked.
+async fn accept_into_free_slot(
+    server: &Endpoint<Server>,
+    player_slots: &Arc<PlayerSlots>,
+) -> (PlayerSlotGuard, IncomingSession) {
+    let player_slot = player_slots.claim().await;
+    let incoming_session = server.accept().await;
+
+    (player_slot, incoming_session)
+}
+
+async fn wait_for_world_updates_to_stop(mut receiver: Receiver<WorldData>) {
+    while receiver.changed().await.is_ok() {}
+}
+
+async fn spawn_world_data_broadcast(
+    mut receive_channel: mpsc::UnboundedReceiver<WorldData>,
+) -> Option<[Receiver<WorldData>; 2]> {
+    let initial_world_data = receive_channel.recv().await?;
+
+    let (player_1_sender, player_1_receiver) = channel(initial_world_data.clone());
+    let (player_2_sender, player_2_receiver) = channel(initial_world_data);
+
+    tokio::spawn(async move {
+        while let Some(data) = receive_channel.recv().await {
+            let _ = player_1_sender.send(data.clone());
+            let _ = player_2_sender.send(data);
+        }
+    });
+
+    Some([player_1_receiver, player_2_receiver])
+}
+
+fn create_server_info(server_arguments: &ServerArguments) -> ServerInfo {
+    ServerInfo {
+        protocol_version: PROTOCOL_VERSION,
+        available_modes: GameMode::ALL.iter().map(|m| m.name().to_string()).collect(),
+        active_mode: server_arguments.game_mode.name().to_string(),
+        available_layouts: BlockLayout::ALL
+            .iter()
+            .map(|l| l.name().to_string())
+            .collect(),
+        active_layout: server_arguments.block_layout.name().to_string(),
+        arena_width: WORLD_WIDTH,
+        arena_height: WORLD_HEIGHT,
+        player_capacity: server_arguments.game_mode.player_capacity(),
+        match_settings: server_arguments.match_settings.clone(),
+    }
+}
+
+fn create_network_simulation(
+    server_arguments: &ServerArguments,
+    player_id: u8,
+) -> NetworkSimulation {
+    NetworkSimulation::new(
+        server_arguments.simulated_latency,
+        server_arguments.simulated_drop_percent,
+        server_arguments
+            .match_settings
+            .random_seed
+            .wrapping_add(player_id as u64),
+    )
+}
+
+fn init_logging() {
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    tracing_subscriber::fmt()
+        .with_target(true)
+        .with_level(true)
+        .with_env_filter(env_filter)
+        .init();
+}
+
+async fn handle_connection(
+    incoming_session: IncomingSession,
+    player_slot: PlayerSlotGuard,
+    connection: PlayerConnection,
+) {
+    let player_id = player_slot.player_id;
+
+    let result = handle_connection_impl(incoming_session, player_id, connection).await;
+
+    match &result {
+        Err(error) if is_timeout_error(error.as_ref()) => {
+            error!(
+                "Player {} dropped: no keep-alive response within the idle timeout",
+                player_id
+            );
+        }
+        _ => error!("{:?}", result),
+    }
+}
+
+fn is_timeout_error(error: &(dyn Error + 'static)) -> bool {
+    if let Some(connection_error) = error.downcast_ref::<ConnectionError>() {
+        return matches!(connection_error, ConnectionError::TimedOut);
+    }
+
+    match error.downcast_ref::<std::io::Error>() {
+        Some(io_error) => io_error.kind() == std::io::ErrorKind::TimedOut,
+        None => false,
+    }
+}
+
+async fn handle_connection_impl(
+    incoming_session: IncomingSession,
+    player_id: u8,
+    connection: PlayerConnection,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let PlayerConnection {
+        mut receive_channel,
+        player_key_event_send_channel,
+        server_info,
+        max_snapshot_bytes,
+        full_snapshot_interval_ticks,
+        dynamic_snapshots,
+        connected_players,
+        kick_receiver,
+        mut network_simulation,
+    } = connection;
+
+    info!("Waiting for session request...");
+
+    let session_request = incoming_session.await?;
+
+    info!(
+        "New session: Authority: '{}', Path: '{}'",
+        session_request.authority(),
+        session_request.path()
+    );
+
+    let connection = session_request.accept().await?;
+
+    let (mut send_stream, receive_stream) = connection.accept_bi().await?;
+    send_stream.write_u8(player_id).await?;
+    send_stream.flush().await?;
+
+    send_message(&mut send_stream, &server_info).await?;
+
+    let _connected_player_guard = ConnectedPlayerGuard::new(connected_players);
+
+    let mut kick_receiver = kick_receiver.lock().await;
+    while kick_receiver.try_recv().is_ok() {}
+
+    let (view_region_sender, view_region_receiver) = channel(ViewRegion::Full);
+
+    let client_messages = receive_client_messages(
+        receive_stream,
+        player_id,
+        player_key_event_send_channel,
+        view_region_sender,
+    );
+
+    tokio::pin!(client_messages);
+
+    let mut last_sent_tick: Option<u64> = None;
+    let mut total_skipped_snapshots: u64 = 0;
+    let mut oversized_snapshots: u64 = 0;
+    let mut last_full_snapshot_tick: Option<u64> = None;
+    let mut last_static_state: Option<StaticState> = None;
+
+    loop {
+        tokio::select! {
+            result = &mut client_messages => {
+                return result;
+            }
+            _ = tokio::time::sleep_until(
+                network_simulation.next_due().unwrap_or_else(tokio::time::Instant::now)
+            ), if network_simulation.next_due().is_some() => {
+                while let Some(frame) = network_simulation.take_due_frame() {
+                    send_frame(&mut send_stream, &frame).await?;
+                }
+            }
+            Some(reason) = kick_receiver.recv() => {
+                info!("Kicking Player {}: {}", player_id, reason);
+
+                let message = ServerMessage::Disconnect(DisconnectReason::Kicked(reason));
+                send_message(&mut send_stream, &message).await?;
+                send_stream.finish().await?;
+
+                return Ok(());
+            }
+            changed = receive_channel.changed() => {
+                if changed.is_err() {
+                    info!("World updates stopped, closing Player {} connection", player_id);
+
+                    let is_match_abandoned =
+                        receive_channel.borrow().reconnect_grace_seconds == Some(0);
+
+                    let reason = if is_match_abandoned {
+                        DisconnectReason::MatchEnded
+                    } else {
+                        DisconnectReason::ServerShuttingDown
+                    };
+
+                    let message = ServerMessage::Disconnect(reason);
+                    send_message(&mut send_stream, &message).await?;
+                    send_stream.finish().await?;
+
+                    return Ok(());
+                }
+
+                let world_data = filter_world_data_by_view_region(
+                    clear_server_only_state(receive_channel.borrow_and_update().clone()),
+                    player_id,
+                    *view_region_receiver.borrow(),
+                );
+
+                if let Some(last_sent_tick) = last_sent_tick {
+                    let skipped_snapshots = world_data.tick.saturating_sub(last_sent_tick + 1);
+
+                    if skipped_snapshots > 0 {
+                        total_skipped_snapshots += skipped_snapshots;
+
+                        debug!(
+                            "Player {} skipped {} stale snapshots ({} total)",
+                            player_id, skipped_snapshots, total_skipped_snapshots
+                        );
+                    }
+                }
+
+                last_sent_tick = Some(world_data.tick);
+
+                if network_simulation.is_dropped() {
+                    continue;
+                }
+
+                let send_started_at = Instant::now();
+
+                let tick = world_data.tick;
+
+                let is_full_snapshot = last_full_snapshot_tick
+                    .is_none_or(|last_tick| tick >= last_tick + full_snapshot_interval_ticks);
+
+                let message = if is_full_snapshot {
+                    last_full_snapshot_tick = Some(tick);
+
+                    let static_state = StaticState::from_world_data(&world_data);
+
+                    if dynamic_snapshots && last_static_state.as_ref() == Some(&static_state) {
+                        ServerMessage::Dynamic(DynamicUpdate::from_world_data(&world_data))
+                    } else {
+                        last_static_state = Some(static_state);
+                        ServerMessage::WorldData(world_data)
+                    }
+                } else {
+                    ServerMessage::Hud(HudUpdate::from_world_data(&world_data))
+                };
+
+                let frame = rmp_serde::to_vec(&message)?;
+
+                if frame.len() > max_snapshot_bytes {
+                    oversized_snapshots += 1;
+
+                    if oversized_snapshots == 1 {
+                        warn!(
+                            "Player {} snapshot at tick {} is {} bytes, above the {} byte limit",
+                            player_id,
+                            tick,
+                            frame.len(),
+                            max_snapshot_bytes
+                        );
+                    } else {
+                        debug!(
+                            "Player {} sent {} oversized snapshots so far",
+                            player_id, oversized_snapshots
+                        );
+                    }
+                }
+
+                if let Some(frame) = network_simulation.delay(frame) {
+                    send_frame(&mut send_stream, &frame).await?;
+                }
+
+                if send_started_at.elapsed().as_secs_f32() > GAME_LOOP_TIMESTEP_SECONDS {
+                    debug!(
+                        "Player {} snapshot send took {:?}, newer snapshots will replace pending ones",
+                        player_id,
+                        send_started_at.elapsed()
+                    );
+                }
+            }
+        }
+    }
+}
+
+async fn send_message(
+    send_stream: &mut SendStream,
+    message: &impl Serialize,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let buf = rmp_serde::to_vec(message)?;
+    send_frame(send_stream, &buf).await
+}
+
+async fn send_frame(
+    send_stream: &mut SendStream,
+    frame: &[u8],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let len = frame.len() as u32;
+    send_stream.write_u32(len).await?;
+    send_stream.write_all(frame).await?;
+    send_stream.flush().await?;
+    Ok(())
+}
+
+async fn receive_client_messages(
+    mut receive_stream: impl AsyncRead + Unpin,
+    player_id: u8,
+    player_key_event_send_channel: mpsc::UnboundedSender<PlayerKeyEvent>,
+    view_region_sender: watch::Sender<ViewRegion>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut last_key_sequence: Option<u32> = None;
+
+    loop {
+        let len = receive_stream.read_u32().await?;
+        let mut buffer = vec![0; len as usize];
+        receive_stream.read_exact(&mut buffer).await?;
+
+        let client_message: ClientMessage = rmp_serde::from_slice(&buffer)?;
+
+        match client_message {
+            ClientMessage::Key { sequence, key_code } => {
+                if !is_newer_sequence(last_key_sequence, sequence) {
+                    continue;
+                }
+
+                last_key_sequence = Some(sequence);
+
+                player_key_event_send_channel.send(PlayerKeyEvent {
+                    player_id,
+                    key_code,
+                })?;
+            }
+            ClientMessage::ViewRegion(view_region) => {
+                info!(
+                    "Player {} switched view region to {:?}",
+                    player_id, view_region
+                );
+                view_region_sender.send_replace(view_region);
+            }
+        }
+    }
+}
+
+// Sequences wrap around, so anything up to half the u32 range ahead of the last one is newer.
+fn is_newer_sequence(last_sequence: Option<u32>, sequence: u32) -> bool {
+    match last_sequence {
+        Some(last_sequence) => (sequence.wrapping_sub(last_sequence) as i32) > 0,
+        None => true,
+    }
+}
+
+fn filter_world_data_by_view_region(
+    mut world_data: WorldData,
+    player_id: u8,
+    view_region: ViewRegion,
+) -> WorldData {
+    if view_region == ViewRegion::Full {
+        return world_data;
+    }
+
+    let is_in_own_half = |position: Vector2<f32>| {
+        if player_id == 0 {
+            position.y >= WORLD_HEIGHT as f32 / 2.0 - BLOCK_SIZE as f32
+        } else {
+            position.y <= WORLD_HEIGHT as f32 / 2.0 + BLOCK_SIZE as f32
+        }
+    };
+
+    world_data.blocks.retain(|b| is_in_own_half(b.position));
+    world_data
+        .regenerating_blocks
+        .retain(|b| is_in_own_half(b.position));
+    world_data.paddles.retain(|p| is_in_own_half(p.position));
+    world_data.balls.retain(|b| is_in_own_half(b.position));
+
+    world_data
+}
+
+// Serve timers and collision cooldowns only drive the simulation, so clients get them zeroed.
+// They stay in the game state itself, which is what match saves are written from.
+fn clear_server_only_state(mut world_data: WorldData) -> WorldData {
+    for ball in world_data.balls.iter_mut() {
+        ball.attached_ticks = 0;
+        ball.paddle_collision_cooldown_ticks = 0;
+    }
+
+    world_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each key event carries its sequence as the key code, so the forwarded events show which
+    // sequences the real receive loop accepted.
+    async fn forward_key_sequences(sequences: &[u32]) -> Vec<u32> {
+        let mut frames = vec![];
+
+        for sequence in sequences {
+            let message = rmp_serde::to_vec(&ClientMessage::Key {
+                sequence: *sequence,
+                key_code: *sequence,
+            })
+            .unwrap();
+
+            frames.extend((message.len() as u32).to_be_bytes());
+            frames.extend(message);
+        }
+
+        let (key_event_sender, mut key_event_receiver) = mpsc::unbounded_channel();
+        let (view_region_sender, _view_region_receiver) = channel(ViewRegion::Full);
+
+        let result =
+            receive_client_messages(&frames[..], 1, key_event_sender, view_region_sender).await;
+        assert!(result.is_err());
+
+        let mut forwarded_sequences = vec![];
+
+        while let Ok(event) = key_event_receiver.try_recv() {
+            assert_eq!(event.player_id, 1);
+            forwarded_sequences.push(event.key_code);
+        }
+
+        forwarded_sequences
+    }
+
+    #[tokio::test]
+    async fn stale_key_sequences_are_ignored() {
+        assert_eq!(
+            forward_key_sequences(&[1, 2, 4, 3, 4, 5, 2, 6]).await,
+            vec![1, 2, 4, 5, 6]
+        );
+    }
+
+    #[tokio::test]
+    async fn key_sequences_keep_flowing_after_wraparound() {
+        assert_eq!(
+            forward_key_sequences(&[u32::MAX - 1, u32::MAX, 0, u32::MAX, 1]).await,
+            vec![u32::MAX - 1, u32::MAX, 0, 1]
+        );
+    }
+
+    #[test]
+    fn newer_sequence_handles_wraparound() {
+        assert!(is_newer_sequence(Some(u32::MAX), 0));
+        assert!(is_newer_sequence(Some(u32::MAX - 5), 3));
+        assert!(!is_newer_sequence(Some(0), u32::MAX));
+        assert!(!is_newer_sequence(Some(3), u32::MAX - 5));
+        assert!(is_newer_sequence(Some(7), 8));
+        assert!(!is_newer_sequence(Some(8), 7));
+    }
+
+    #[test]
+    fn first_key_sequence_is_always_accepted() {
+        assert!(is_newer_sequence(None, 0));
+        assert!(is_newer_sequence(None, u32::MAX));
+        assert!(!is_newer_sequence(Some(7), 7));
+    }
+
+    fn parse_test_arguments(arguments: &[&str]) -> Result<ServerArguments, String> {
+        parse_server_arguments(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    #[test]
+    fn server_info_reflects_configured_settings() {
+        let server_arguments = parse_test_arguments(&[
+            "--mode",
+            "practice",
+            "--layout",
+            "pyramid",
+            "--gravity",
+            "120",
+            "--balls-per-player",
+            "3",
+            "--collision-margin",
+            "2",
+        ])
+        .unwrap();
+
+        let server_info = create_server_info(&server_arguments);
+
+        assert_eq!(server_info.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(server_info.active_mode, "practice");
+        assert_eq!(server_info.active_layout, "pyramid");
+        assert_eq!(server_info.player_capacity, 1);
+        assert_eq!(server_info.arena_width, WORLD_WIDTH);
+        assert_eq!(server_info.arena_height, WORLD_HEIGHT);
+        assert_eq!(server_info.match_settings.gravity, 120.0);
+        assert_eq!(server_info.match_settings.balls_per_player, 3);
+        assert_eq!(server_info.match_settings.collision_margin, 2.0);
+        assert_eq!(server_info.match_settings, server_arguments.match_settings);
+    }
+
+    #[test]
+    fn seed_argument_reaches_server_info_and_the_game() {
+        let server_arguments = parse_test_arguments(&["--seed", "987654321"]).unwrap();
+
+        let server_info = create_server_info(&server_arguments);
+        let game_state = game::create_world(
+            server_arguments.block_layout,
+            server_arguments.game_mode,
+            server_arguments.match_settings.clone(),
+        );
+
+        assert_eq!(server_info.match_settings.random_seed, 987654321);
+        assert_eq!(game_state.match_settings.random_seed, 987654321);
+    }
+
+    #[test]
+    fn server_info_lists_every_mode_and_layout() {
+        let server_info = create_server_info(&parse_test_arguments(&[]).unwrap());
+
+        assert_eq!(server_info.active_mode, "versus");
+        assert_eq!(server_info.player_capacity, 2);
+        assert_eq!(
+            server_info.available_modes,
+            vec!["versus", "practice", "shared"]
+        );
+        assert_eq!(
+            server_info.available_layouts,
+            vec!["grid", "pyramid", "checkerboard", "fortress"]
+        );
+        assert_eq!(server_info.match_settings, MatchSettings::default());
+    }
+
+    #[test]
+    fn snapshots_leave_out_server_only_ball_timers() {
+        let mut world_data = game::create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        )
+        .world_data;
+
+        world_data.balls[0].attached_ticks = 90;
+        world_data.balls[0].paddle_collision_cooldown_ticks = 4;
+        world_data.balls[0].breakthrough_ticks = 30;
+
+        let sent_world_data = clear_server_only_state(world_data.clone());
+
+        assert_eq!(sent_world_data.balls[0].attached_ticks, 0);
+        assert_eq!(sent_world_data.balls[0].paddle_collision_cooldown_ticks, 0);
+        assert_eq!(sent_world_data.balls[0].breakthrough_ticks, 30);
+        assert_eq!(sent_world_data.balls[1], world_data.balls[1]);
+    }
+
+    #[test]
+    fn breakthrough_admin_command_names_a_player() {
+        assert!(matches!(
+            parse_admin_command("breakthrough 1"),
+            Ok(Some(AdminCommand::Breakthrough { player_id: 1 }))
+        ));
+        assert_eq!(
+            parse_admin_command("breakthrough").err(),
+            Some("breakthrough requires a player id (0 or 1)".to_string())
+        );
+        assert_eq!(
+            parse_admin_command("breakthrough 2").err(),
+            Some("breakthrough expects a player id of 0 or 1, got '2'".to_string())
+        );
+    }
+
+    struct TestGameLoop {
+        world_data_receiver: mpsc::UnboundedReceiver<WorldData>,
+        connected_players: Arc<AtomicU8>,
+        handle: tokio::task::JoinHandle<()>,
+        key_event_sender: mpsc::UnboundedSender<PlayerKeyEvent>,
+        _game_loop_command_sender: mpsc::UnboundedSender<GameLoopCommand>,
+    }
+
+    fn spawn_test_game_loop(
+        connected_players: u8,
+        required_players: u8,
+        reconnect_grace_seconds: Option<u16>,
+    ) -> TestGameLoop {
+        spawn_test_game_loop_with_arguments(
+            connected_players,
+            ServerArguments {
+                required_players,
+                reconnect_grace_seconds,
+                countdown_seconds: 0,
+                input_buffer_depth: 0,
+                ..parse_test_arguments(&[]).unwrap()
+            },
+        )
+    }
+
+    fn spawn_test_game_loop_with_arguments(
+        connected_players: u8,
+        server_arguments: ServerArguments,
+    ) -> TestGameLoop {
+        let (world_data_sender, world_data_receiver) = mpsc::unbounded_channel();
+        let (key_event_sender, key_event_receiver) = mpsc::unbounded_channel();
+        let (game_loop_command_sender, game_loop_command_receiver) = mpsc::unbounded_channel();
+        let connected_players = Arc::new(AtomicU8::new(connected_players));
+
+        let handle = tokio::spawn(start_game_loop(
+            server_arguments,
+            world_data_sender,
+            key_event_receiver,
+            Arc::new(Mutex::new(CrashReplayBuffer::new(1))),
+            connected_players.clone(),
+            None,
+            game_loop_command_receiver,
+        ));
+
+        TestGameLoop {
+            world_data_receiver,
+            connected_players,
+            handle,
+            key_event_sender,
+            _game_loop_command_sender: game_loop_command_sender,
+        }
+    }
+
+    async fn receive_until(
+        world_data_receiver: &mut mpsc::UnboundedReceiver<WorldData>,
+        is_expected: impl Fn(&WorldData) -> bool,
+    ) -> WorldData {
+        for _ in 0..600 {
+            let world_data = world_data_receiver
+                .recv()
+                .await
+                .expect("Game loop stopped broadcasting");
+
+            if is_expected(&world_data) {
+                return world_data;
+            }
+        }
+
+        panic!("Game loop never reached the expected state");
+    }
+
+    #[tokio::test]
+    async fn match_does_not_start_until_required_players_connect() {
+        let mut game_loop = spawn_test_game_loop(1, 2, None);
+
+        for _ in 0..10 {
+            let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+
+            assert_eq!(world_data.waiting_for_players, Some(1));
+            assert_eq!(world_data.tick, 0);
+        }
+
+        game_loop.connected_players.store(2, Ordering::SeqCst);
+
+        let world_data = receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.waiting_for_players.is_none()
+        })
+        .await;
+        assert_eq!(world_data.tick, 1);
+
+        let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+        assert_eq!(world_data.tick, 2);
+
+        game_loop.handle.abort();
+    }
+
+    #[tokio::test]
+    async fn single_required_player_starts_the_match_alone() {
+        let mut game_loop = spawn_test_game_loop(1, 1, None);
+
+        let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+
+        assert_eq!(world_data.waiting_for_players, None);
+        assert_eq!(world_data.tick, 1);
+
+        game_loop.handle.abort();
+    }
+
+    #[tokio::test]
+    async fn match_countdown_does_not_run_while_paused() {
+        let mut game_loop = spawn_test_game_loop_with_arguments(
+            2,
+            ServerArguments {
+                countdown_seconds: 3,
+                input_buffer_depth: 0,
+                ..parse_test_arguments(&[]).unwrap()
+            },
+        );
+        let toggle_pause = || {
+            game_loop
+                .key_event_sender
+                .send(PlayerKeyEvent {
+                    player_id: 0,
+                    key_code: KeyboardKey::KEY_P as u32,
+                })
+                .unwrap();
+        };
+
+        receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.match_countdown.is_some()
+        })
+        .await;
+
+        toggle_pause();
+
+        let paused_world_data = receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.is_paused
+        })
+        .await;
+        assert!(paused_world_data.match_countdown.is_some());
+
+        for _ in 0..90 {
+            let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+
+            assert!(world_data.is_paused);
+            assert_eq!(
+                world_data.match_countdown,
+                paused_world_data.match_countdown
+            );
+        }
+
+        toggle_pause();
+
+        receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            !world_data.is_paused && world_data.match_countdown.is_none()
+        })
+        .await;
+
+        game_loop.handle.abort();
+    }
+
+    #[tokio::test]
+    async fn match_is_abandoned_when_grace_period_runs_out() {
+        let mut game_loop = spawn_test_game_loop(2, 2, Some(1));
+
+        receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.waiting_for_players.is_none()
+        })
+        .await;
+
+        game_loop.connected_players.store(1, Ordering::SeqCst);
+
+        let world_data = receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.reconnect_grace_seconds == Some(0)
+        })
+        .await;
+        assert_eq!(world_data.waiting_for_players, Some(1));
+
+        assert!(game_loop.world_data_receiver.recv().await.is_none());
+        game_loop.handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn match_resumes_when_player_reconnects_within_grace_period() {
+        let mut game_loop = spawn_test_game_loop(2, 2, Some(5));
+
+        receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.waiting_for_players.is_none()
+        })
+        .await;
+
+        game_loop.connected_players.store(1, Ordering::SeqCst);
+
+        let paused_world_data = receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.reconnect_grace_seconds == Some(5)
+        })
+        .await;
+        let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+        assert_eq!(world_data.tick, paused_world_data.tick);
+
+        game_loop.connected_players.store(2, Ordering::SeqCst);
+
+        let resumed_world_data = receive_until(&mut game_loop.world_data_receiver, |world_data| {
+            world_data.waiting_for_players.is_none()
+        })
+        .await;
+        assert_eq!(resumed_world_data.reconnect_grace_seconds, None);
+        assert_eq!(resumed_world_data.tick, paused_world_data.tick + 1);
+
+        let world_data = game_loop.world_data_receiver.recv().await.unwrap();
+        assert_eq!(world_data.tick, resumed_world_data.tick + 1);
+        assert!(!game_loop.handle.is_finished());
+
+        game_loop.handle.abort();
+    }
+
+    #[tokio::test]
+    async fn broadcast_is_not_started_if_game_loop_stops_before_initial_world() {
+        let (world_data_sender, world_data_receiver) = mpsc::unbounded_channel::<WorldData>();
+        drop(world_data_sender);
+
+        assert!(spawn_world_data_broadcast(world_data_receiver)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn broadcast_starts_both_players_from_initial_world() {
+        let (world_data_sender, world_data_receiver) = mpsc::unbounded_channel();
+        let mut world_data = game::create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        )
+        .world_data;
+
+        world_data_sender.send(world_data.clone()).unwrap();
+
+        let [mut player_1_receiver, mut player_2_receiver] =
+            spawn_world_data_broadcast(world_data_receiver)
+                .await
+                .unwrap();
+
+        assert_eq!(player_1_receiver.borrow().tick, 0);
+        assert_eq!(player_2_receiver.borrow().tick, 0);
+
+        world_data.tick = 1;
+        world_data_sender.send(world_data).unwrap();
+
+        player_1_receiver.changed().await.unwrap();
+        player_2_receiver.changed().await.unwrap();
+
+        assert_eq!(player_1_receiver.borrow().tick, 1);
+        assert_eq!(player_2_receiver.borrow().tick, 1);
+    }
+
+    #[test]
+    fn player_slots_are_limited_by_capacity() {
+        let player_slots = PlayerSlots::new(2);
+
+        let player_0_slot = player_slots.try_claim().unwrap();
+        let player_1_slot = player_slots.try_claim().unwrap();
+
+        assert_eq!(player_0_slot.player_id, 0);
+        assert_eq!(player_1_slot.player_id, 1);
+        assert!(player_slots.try_claim().is_none());
+
+        assert!(PlayerSlots::new(1).try_claim().is_some());
+    }
+
+    #[tokio::test]
+    async fn freed_slot_is_handed_to_the_next_session() {
+        let player_slots = PlayerSlots::new(2);
+
+        let player_0_slot = player_slots.claim().await;
+        let _player_1_slot = player_slots.claim().await;
+
+        let waiting_player_slots = player_slots.clone();
+        let next_slot = tokio::spawn(async move { waiting_player_slots.claim().await.player_id });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!next_slot.is_finished());
+
+        drop(player_0_slot);
+
+        let next_player_id = tokio::time::timeout(Duration::from_secs(1), next_slot)
+            .await
+            .expect("Freed slot was never handed out")
+            .unwrap();
+
+        assert_eq!(next_player_id, 0);
+    }
+
+    struct TestClient {
+        _connection: wtransport::Connection,
+        _send_stream: SendStream,
+        receive_stream: wtransport::RecvStream,
+    }
+
+    impl TestClient {
+        async fn read_server_message(&mut self) -> Option<ServerMessage> {
+            let len = self.receive_stream.read_u32().await.ok()?;
+            let mut buffer = vec![0; len as usize];
+            self.receive_stream.read_exact(&mut buffer).await.ok()?;
+
+            Some(rmp_serde::from_slice(&buffer).unwrap())
+        }
+    }
+
+    async fn connect_test_client(port: u16) -> TestClient {
+        let config = wtransport::ClientConfig::builder()
+            .with_bind_default()
+            .with_no_cert_validation()
+            .build();
+        let connection = wtransport::Endpoint::client(config)
+            .unwrap()
+            .connect(format!("https://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+
+        let (send_stream, mut receive_stream) = connection.open_bi().await.unwrap().await.unwrap();
+
+        receive_stream.read_u8().await.unwrap();
+        let len = receive_stream.read_u32().await.unwrap();
+        let mut server_info = vec![0; len as usize];
+        receive_stream.read_exact(&mut server_info).await.unwrap();
+
+        TestClient {
+            _connection: connection,
+            _send_stream: send_stream,
+            receive_stream,
+        }
+    }
+
+    #[tokio::test]
+    async fn kick_command_closes_only_the_targeted_connection() {
+        let identity = Identity::self_signed(["localhost", "127.0.0.1"]).unwrap();
+        let config = ServerConfig::builder()
+            .with_bind_address("127.0.0.1:0".parse().unwrap())
+            .with_identity(&identity)
+            .build();
+        let server = Endpoint::server(config).unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_info = create_server_info(&parse_test_arguments(&[]).unwrap());
+        let world_data = game::create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        )
+        .world_data;
+        let (world_data_sender, world_data_receiver) = channel(world_data.clone());
+        let (key_event_sender, _key_event_receiver) = mpsc::unbounded_channel();
+        let (game_loop_command_sender, _game_loop_command_receiver) = mpsc::unbounded_channel();
+        let connected_players = Arc::new(AtomicU8::new(0));
+
+        let (player_0_kick_sender, player_0_kick_receiver) = mpsc::unbounded_channel();
+        let (player_1_kick_sender, player_1_kick_receiver) = mpsc::unbounded_channel();
+        let kick_senders = [player_0_kick_sender, player_1_kick_sender];
+        let kick_receivers = [player_0_kick_receiver, player_1_kick_receiver]
+            .map(|receiver| Arc::new(AsyncMutex::new(receiver)));
+
+        let mut clients = vec![];
+        let mut connection_handles = vec![];
+
+        for player_id in 0..2u8 {
+            let connection = PlayerConnection {
+                receive_channel: world_data_receiver.clone(),
+                player_key_event_send_channel: key_event_sender.clone(),
+                server_info: server_info.clone(),
+                max_snapshot_bytes: DEFAULT_MAX_SNAPSHOT_BYTES,
+                full_snapshot_interval_ticks: 1,
+                dynamic_snapshots: false,
+                connected_players: connected_players.clone(),
+                kick_receiver: kick_receivers[player_id as usize].clone(),
+                network_simulation: NetworkSimulation::new(Duration::ZERO, 0, 0),
+            };
+
+            let (client, ()) = tokio::join!(connect_test_client(port), async {
+                let incoming_session = server.accept().await;
+                connection_handles.push(tokio::spawn(handle_connection_impl(
+                    incoming_session,
+                    player_id,
+                    connection,
+                )));
+            });
+
+            clients.push(client);
+        }
+
+        let mut tick = 0;
+        let mut send_world_update = || {
+            tick += 1;
+            world_data_sender.send_modify(|world_data| world_data.tick = tick);
+        };
+
+        send_world_update();
+
+        for client in clients.iter_mut() {
+            assert!(matches!(
+                client.read_server_message().await,
+                Some(ServerMessage::WorldData(_))
+            ));
+        }
+
+        assert_eq!(connected_players.load(Ordering::SeqCst), 2);
+
+        run_admin_command("kick 0 too slow", &kick_senders, &game_loop_command_sender);
+
+        assert_eq!(
+            clients[0].read_server_message().await,
+            Some(ServerMessage::Disconnect(DisconnectReason::Kicked(
+                "too slow".to_string()
+            )))
+        );
+        assert_eq!(clients[0].read_server_message().await, None);
+
+        let kicked_handle = connection_handles.remove(0);
+        assert!(kicked_handle.await.unwrap().is_ok());
+        assert_eq!(connected_players.load(Ordering::SeqCst), 1);
+
+        send_world_update();
+
+        match clients[1].read_server_message().await {
+            Some(ServerMessage::WorldData(world_data)) => assert_eq!(world_data.tick, 2),
+            message => panic!("Player 1 should still get updates, got {:?}", message),
+        }
+
+        assert!(!connection_handles[0].is_finished());
+        connection_handles[0].abort();
+    }
 }
@@ -0,0 +1,139 @@
+use shared::random::SeededRandom;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub const MAX_SIMULATED_LATENCY_MS: u64 = 2000;
+pub const MAX_SIMULATED_DROP_PERCENT: u8 = 90;
+
+/// Debug-only degradation of a player's outgoing snapshots, for exercising interpolation and
+/// reconnection locally. Delayed frames are queued rather than slept on, so latency doesn't also
+/// throttle the snapshot rate.
+pub struct NetworkSimulation {
+    latency: Duration,
+    drop_chance: f32,
+    random: SeededRandom,
+    delayed_frames: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl NetworkSimulation {
+    pub fn new(latency: Duration, drop_percent: u8, seed: u64) -> Self {
+        NetworkSimulation {
+            latency,
+            drop_chance: drop_percent as f32 / 100.0,
+            random: SeededRandom::new(seed),
+            delayed_frames: VecDeque::new(),
+        }
+    }
+
+    pub fn is_dropped(&mut self) -> bool {
+        if self.drop_chance == 0.0 {
+            return false;
+        }
+
+        let unit = (self.random.next_signed_unit() + 1.0) / 2.0;
+        unit < self.drop_chance
+    }
+
+    /// Returns the frame back when it should be sent right away, otherwise keeps it until it's due.
+    pub fn delay(&mut self, frame: Vec<u8>) -> Option<Vec<u8>> {
+        if self.latency.is_zero() {
+            return Some(frame);
+        }
+
+        self.delayed_frames
+            .push_back((Instant::now() + self.latency, frame));
+
+        None
+    }
+
+    pub fn next_due(&self) -> Option<Instant> {
+        self.delayed_frames.front().map(|(due, _)| *due)
+    }
+
+    pub fn take_due_frame(&mut self) -> Option<Vec<u8>> {
+        match self.delayed_frames.front() {
+            Some((due, _)) if *due <= Instant::now() => {
+                self.delayed_frames.pop_front().map(|(_, frame)| frame)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DROP_SAMPLES: usize = 10_000;
+
+    fn count_dropped(network_simulation: &mut NetworkSimulation) -> usize {
+        (0..DROP_SAMPLES)
+            .filter(|_| network_simulation.is_dropped())
+            .count()
+    }
+
+    #[test]
+    fn zero_latency_sends_frames_unchanged() {
+        let mut network_simulation = NetworkSimulation::new(Duration::ZERO, 0, 1);
+
+        for frame in [vec![], vec![1, 2, 3], vec![255; 64]] {
+            assert_eq!(network_simulation.delay(frame.clone()), Some(frame));
+        }
+
+        assert_eq!(network_simulation.next_due(), None);
+        assert_eq!(network_simulation.take_due_frame(), None);
+        assert_eq!(count_dropped(&mut network_simulation), 0);
+    }
+
+    #[test]
+    fn delayed_frames_are_held_until_due_and_keep_their_order() {
+        let latency = Duration::from_millis(50);
+        let mut network_simulation = NetworkSimulation::new(latency, 0, 1);
+        let sent_at = Instant::now();
+
+        assert_eq!(network_simulation.delay(vec![1]), None);
+        assert_eq!(network_simulation.delay(vec![2]), None);
+
+        assert!(network_simulation.next_due().unwrap() >= sent_at + latency);
+        assert_eq!(network_simulation.take_due_frame(), None);
+
+        std::thread::sleep(latency);
+
+        assert_eq!(network_simulation.take_due_frame(), Some(vec![1]));
+        assert_eq!(network_simulation.take_due_frame(), Some(vec![2]));
+        assert_eq!(network_simulation.take_due_frame(), None);
+        assert_eq!(network_simulation.next_due(), None);
+    }
+
+    #[test]
+    fn drop_rate_follows_the_configured_percent() {
+        for drop_percent in [10, 50, MAX_SIMULATED_DROP_PERCENT] {
+            let mut network_simulation = NetworkSimulation::new(Duration::ZERO, drop_percent, 7);
+
+            let expected = DROP_SAMPLES * drop_percent as usize / 100;
+            let dropped = count_dropped(&mut network_simulation);
+
+            assert!(
+                dropped.abs_diff(expected) < DROP_SAMPLES / 50,
+                "{}% drop rate dropped {} of {}",
+                drop_percent,
+                dropped,
+                DROP_SAMPLES
+            );
+        }
+    }
+
+    #[test]
+    fn drops_repeat_for_the_same_seed() {
+        let drops = |seed| {
+            let mut network_simulation = NetworkSimulation::new(Duration::ZERO, 50, seed);
+            (0..100)
+                .map(|_| network_simulation.is_dropped())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(drops(3), drops(3));
+        assert_ne!(drops(3), drops(4));
+    }
+}
@@ -0,0 +1,103 @@
+use shared::game::GameState;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+pub fn write_to_file(game_state: &GameState, path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    rmp_serde::encode::write(&mut writer, game_state)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub fn read_from_file(path: &Path) -> Result<GameState, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    Ok(rmp_serde::from_read(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::block_layouts::BlockLayout;
+    use shared::game::{create_world, step_world, GameMode, PlayerAction, PlayerInput};
+    use shared::match_settings::MatchSettings;
+
+    fn step_with_inputs(game_state: &mut GameState, ticks: usize) {
+        for tick in 0..ticks {
+            let action = if tick % 2 == 0 {
+                PlayerAction::MoveLeft
+            } else {
+                PlayerAction::Launch
+            };
+
+            step_world(
+                game_state,
+                &[
+                    PlayerInput {
+                        player_id: 0,
+                        action,
+                    },
+                    PlayerInput {
+                        player_id: 1,
+                        action: PlayerAction::Launch,
+                    },
+                ],
+            );
+        }
+    }
+
+    fn save_and_reload(game_state: &GameState, name: &str) -> GameState {
+        let path = std::env::temp_dir().join(format!(
+            "ping-pong-arkanoid-saved-state-{}-{}.bin",
+            std::process::id(),
+            name
+        ));
+        write_to_file(game_state, &path).unwrap();
+        let loaded_state = read_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        loaded_state
+    }
+
+    #[test]
+    fn serve_timers_and_collision_cooldowns_are_saved() {
+        let mut game_state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        game_state.world_data.balls[0].attached_ticks = 90;
+        game_state.world_data.balls[1].paddle_collision_cooldown_ticks = 4;
+
+        let loaded_state = save_and_reload(&game_state, "timers");
+
+        assert_eq!(loaded_state.world_data.balls[0].attached_ticks, 90);
+        assert_eq!(
+            loaded_state.world_data.balls[1].paddle_collision_cooldown_ticks,
+            4
+        );
+    }
+
+    #[test]
+    fn saved_match_reloads_and_continues_identically() {
+        let match_settings = MatchSettings {
+            block_regeneration_seconds: Some(2),
+            ..MatchSettings::default()
+        };
+        let mut game_state = create_world(BlockLayout::Grid, GameMode::Versus, match_settings);
+        step_with_inputs(&mut game_state, 300);
+
+        let mut loaded_state = save_and_reload(&game_state, "continues");
+
+        assert_eq!(loaded_state.world_data, game_state.world_data);
+        assert_eq!(loaded_state.game_mode, game_state.game_mode);
+
+        step_with_inputs(&mut game_state, 600);
+        step_with_inputs(&mut loaded_state, 600);
+
+        assert_eq!(loaded_state.world_data, game_state.world_data);
+    }
+}
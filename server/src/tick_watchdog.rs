@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+const ENGAGE_AFTER_SLOW_TICKS: u32 = 3;
+const DISENGAGE_AFTER_FAST_TICKS: u32 = 60;
+
+pub const DEGRADED_BROADCAST_INTERVAL_TICKS: u64 = 2;
+
+pub enum WatchdogChange {
+    Engaged,
+    Disengaged,
+}
+
+pub struct TickWatchdog {
+    tick_budget: Duration,
+    is_degraded: bool,
+    slow_ticks_streak: u32,
+    fast_ticks_streak: u32,
+}
+
+impl TickWatchdog {
+    pub fn new(tick_budget: Duration) -> Self {
+        TickWatchdog {
+            tick_budget,
+            is_degraded: false,
+            slow_ticks_streak: 0,
+            fast_ticks_streak: 0,
+        }
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.is_degraded
+    }
+
+    // A few slow ticks in a row engage degradation, and a much longer run of fast ticks is
+    // needed to disengage it, so the server doesn't flap around the budget.
+    pub fn record(&mut self, tick_duration: Duration) -> Option<WatchdogChange> {
+        if tick_duration > self.tick_budget {
+            self.slow_ticks_streak += 1;
+            self.fast_ticks_streak = 0;
+        } else {
+            self.fast_ticks_streak += 1;
+            self.slow_ticks_streak = 0;
+        }
+
+        if !self.is_degraded && self.slow_ticks_streak >= ENGAGE_AFTER_SLOW_TICKS {
+            self.is_degraded = true;
+            return Some(WatchdogChange::Engaged);
+        }
+
+        if self.is_degraded && self.fast_ticks_streak >= DISENGAGE_AFTER_FAST_TICKS {
+            self.is_degraded = false;
+            return Some(WatchdogChange::Disengaged);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK_BUDGET: Duration = Duration::from_millis(16);
+    const SLOW_TICK: Duration = Duration::from_millis(20);
+    const FAST_TICK: Duration = Duration::from_millis(5);
+
+    #[test]
+    fn engages_after_consecutive_slow_ticks() {
+        let mut tick_watchdog = TickWatchdog::new(TICK_BUDGET);
+
+        for _ in 0..ENGAGE_AFTER_SLOW_TICKS - 1 {
+            assert!(tick_watchdog.record(SLOW_TICK).is_none());
+        }
+
+        assert!(matches!(
+            tick_watchdog.record(SLOW_TICK),
+            Some(WatchdogChange::Engaged)
+        ));
+        assert!(tick_watchdog.is_degraded());
+        assert!(tick_watchdog.record(SLOW_TICK).is_none());
+    }
+
+    #[test]
+    fn fast_tick_resets_the_slow_streak() {
+        let mut tick_watchdog = TickWatchdog::new(TICK_BUDGET);
+
+        for _ in 0..10 {
+            for _ in 0..ENGAGE_AFTER_SLOW_TICKS - 1 {
+                assert!(tick_watchdog.record(SLOW_TICK).is_none());
+            }
+
+            assert!(tick_watchdog.record(FAST_TICK).is_none());
+        }
+
+        assert!(!tick_watchdog.is_degraded());
+    }
+
+    #[test]
+    fn disengages_only_after_a_long_run_of_fast_ticks() {
+        let mut tick_watchdog = TickWatchdog::new(TICK_BUDGET);
+
+        for _ in 0..ENGAGE_AFTER_SLOW_TICKS {
+            tick_watchdog.record(SLOW_TICK);
+        }
+
+        for _ in 0..DISENGAGE_AFTER_FAST_TICKS - 1 {
+            assert!(tick_watchdog.record(FAST_TICK).is_none());
+        }
+
+        tick_watchdog.record(SLOW_TICK);
+
+        for _ in 0..DISENGAGE_AFTER_FAST_TICKS - 1 {
+            assert!(tick_watchdog.record(FAST_TICK).is_none());
+        }
+
+        assert!(tick_watchdog.is_degraded());
+        assert!(matches!(
+            tick_watchdog.record(FAST_TICK),
+            Some(WatchdogChange::Disengaged)
+        ));
+        assert!(!tick_watchdog.is_degraded());
+    }
+
+    #[test]
+    fn tick_exactly_on_budget_counts_as_fast() {
+        let mut tick_watchdog = TickWatchdog::new(TICK_BUDGET);
+
+        for _ in 0..ENGAGE_AFTER_SLOW_TICKS * 2 {
+            assert!(tick_watchdog.record(TICK_BUDGET).is_none());
+        }
+
+        assert!(!tick_watchdog.is_degraded());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::constants::{
+    row_width, BLOCKS_IN_ROW, BLOCK_GAP, BLOCK_SIZE, WORLD_HEIGHT, WORLD_WIDTH,
+};
+use crate::world_data::Block;
+use cgmath::Vector2;
+
+const BLOCK_ROWS: usize = 5;
+const BLOCK_HITS_LIFE: usize = 1;
+
+const PYRAMID_STEP_COLUMNS: usize = 3;
+const FORTRESS_WALL_HITS_LIFE: usize = BLOCK_HITS_LIFE + 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockLayout {
+    Grid,
+    Pyramid,
+    Checkerboard,
+    Fortress,
+}
+
+impl BlockLayout {
+    pub const ALL: [BlockLayout; 4] = [
+        BlockLayout::Grid,
+        BlockLayout::Pyramid,
+        BlockLayout::Checkerboard,
+        BlockLayout::Fortress,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlockLayout::Grid => "grid",
+            BlockLayout::Pyramid => "pyramid",
+            BlockLayout::Checkerboard => "checkerboard",
+            BlockLayout::Fortress => "fortress",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BlockLayout> {
+        match name {
+            "grid" => Some(BlockLayout::Grid),
+            "pyramid" => Some(BlockLayout::Pyramid),
+            "checkerboard" => Some(BlockLayout::Checkerboard),
+            "fortress" => Some(BlockLayout::Fortress),
+            _ => None,
+        }
+    }
+}
+
+pub fn create_blocks(layout: BlockLayout) -> Vec<Block> {
+    match layout {
+        BlockLayout::Grid => create_grid_blocks(),
+        BlockLayout::Pyramid => create_pyramid_blocks(),
+        BlockLayout::Checkerboard => create_checkerboard_blocks(),
+        BlockLayout::Fortress => create_fortress_blocks(),
+    }
+}
+
+fn create_grid_blocks() -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+
+    for row_index in 0..BLOCK_ROWS {
+        for column_index in 0..BLOCKS_IN_ROW {
+            blocks.push(create_centered_block(
+                column_index,
+                row_index,
+                BLOCK_HITS_LIFE,
+            ));
+        }
+    }
+
+    blocks
+}
+
+fn create_pyramid_blocks() -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+
+    for row_index in 0..BLOCK_ROWS {
+        let distance_from_center_row =
+            (row_index as isize - (BLOCK_ROWS / 2) as isize).unsigned_abs();
+        let inset = distance_from_center_row * PYRAMID_STEP_COLUMNS;
+
+        for column_index in inset..BLOCKS_IN_ROW - inset {
+            blocks.push(create_centered_block(
+                column_index,
+                row_index,
+                BLOCK_HITS_LIFE,
+            ));
+        }
+    }
+
+    blocks
+}
+
+fn create_checkerboard_blocks() -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+
+    for row_index in 0..BLOCK_ROWS {
+        for column_index in 0..BLOCKS_IN_ROW {
+            if (row_index + column_index) % 2 == 0 {
+                blocks.push(create_centered_block(
+                    column_index,
+                    row_index,
+                    BLOCK_HITS_LIFE,
+                ));
+            }
+        }
+    }
+
+    blocks
+}
+
+fn create_fortress_blocks() -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+
+    let keep_start_column = BLOCKS_IN_ROW / 3;
+    let keep_end_column = BLOCKS_IN_ROW - keep_start_column;
+
+    for row_index in 0..BLOCK_ROWS {
+        let is_wall_row = row_index == 0 || row_index == BLOCK_ROWS - 1;
+
+        for column_index in 0..BLOCKS_IN_ROW {
+            if is_wall_row {
+                blocks.push(create_centered_block(
+                    column_index,
+                    row_index,
+                    FORTRESS_WALL_HITS_LIFE,
+                ));
+            } else if (keep_start_column..keep_end_column).contains(&column_index) {
+                blocks.push(create_centered_block(
+                    column_index,
+                    row_index,
+                    BLOCK_HITS_LIFE,
+                ));
+            }
+        }
+    }
+
+    blocks
+}
+
+fn create_centered_block(column_index: usize, row_index: usize, hits_life: usize) -> Block {
+    let row_width = row_width(BLOCKS_IN_ROW, BLOCK_SIZE, BLOCK_GAP);
+    let row_start_x = (WORLD_WIDTH - row_width) as f32 / 2.0;
+
+    Block {
+        id: 0,
+        position: Vector2::new(
+            row_start_x
+                + (column_index * (BLOCK_SIZE + BLOCK_GAP)) as f32
+                + (BLOCK_SIZE as f32 / 2.0),
+            row_position_y(row_index),
+        ),
+        hits_life,
+    }
+}
+
+fn row_position_y(row_index: usize) -> f32 {
+    (row_index * (BLOCK_SIZE + BLOCK_GAP)) as f32
+        + (BLOCK_SIZE as f32 / 2.0)
+        + (WORLD_HEIGHT as f32 / 2.0)
+        - (BLOCK_SIZE as f32 * 2.0 + BLOCK_SIZE as f32 / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_bounds(block: &Block) -> (f32, f32, f32, f32) {
+        let half_size = BLOCK_SIZE as f32 / 2.0;
+
+        (
+            block.position.x - half_size,
+            block.position.x + half_size,
+            block.position.y - half_size,
+            block.position.y + half_size,
+        )
+    }
+
+    #[test]
+    fn layouts_have_expected_block_counts() {
+        assert_eq!(create_blocks(BlockLayout::Grid).len(), 185);
+        assert_eq!(create_blocks(BlockLayout::Pyramid).len(), 149);
+        assert_eq!(create_blocks(BlockLayout::Checkerboard).len(), 93);
+        assert_eq!(create_blocks(BlockLayout::Fortress).len(), 113);
+    }
+
+    #[test]
+    fn layout_blocks_stay_inside_the_arena() {
+        for layout in BlockLayout::ALL {
+            for block in create_blocks(layout) {
+                let (left, right, top, bottom) = block_bounds(&block);
+
+                assert!(
+                    left >= 0.0
+                        && right <= WORLD_WIDTH as f32
+                        && top >= 0.0
+                        && bottom <= WORLD_HEIGHT as f32,
+                    "{} block at {:?} is outside the arena",
+                    layout.name(),
+                    block.position
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn layouts_are_centered_horizontally() {
+        for layout in BlockLayout::ALL {
+            let blocks = create_blocks(layout);
+
+            let leftmost = blocks
+                .iter()
+                .map(|b| block_bounds(b).0)
+                .fold(f32::MAX, f32::min);
+            let rightmost = blocks
+                .iter()
+                .map(|b| block_bounds(b).1)
+                .fold(f32::MIN, f32::max);
+
+            assert!(
+                (leftmost - (WORLD_WIDTH as f32 - rightmost)).abs() <= 1.0,
+                "{} spans {}..{}",
+                layout.name(),
+                leftmost,
+                rightmost
+            );
+        }
+    }
+
+    #[test]
+    fn layout_blocks_do_not_overlap() {
+        for layout in BlockLayout::ALL {
+            let blocks = create_blocks(layout);
+
+            for (index, block) in blocks.iter().enumerate() {
+                for other in &blocks[index + 1..] {
+                    let distance = block.position - other.position;
+
+                    assert!(
+                        distance.x.abs() >= BLOCK_SIZE as f32
+                            || distance.y.abs() >= BLOCK_SIZE as f32,
+                        "{} blocks at {:?} and {:?} overlap",
+                        layout.name(),
+                        block.position,
+                        other.position
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layout_names_round_trip() {
+        for layout in BlockLayout::ALL {
+            assert_eq!(BlockLayout::from_name(layout.name()), Some(layout));
+        }
+
+        assert_eq!(BlockLayout::from_name("spiral"), None);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum ViewRegion {
+    Full,
+    OwnHalf,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum ClientMessage {
+    Key { sequence: u32, key_code: u32 },
+    ViewRegion(ViewRegion),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_messages_round_trip() {
+        let messages = [
+            ClientMessage::Key {
+                sequence: 0,
+                key_code: 32,
+            },
+            ClientMessage::Key {
+                sequence: u32::MAX,
+                key_code: 262,
+            },
+            ClientMessage::ViewRegion(ViewRegion::Full),
+            ClientMessage::ViewRegion(ViewRegion::OwnHalf),
+        ];
+
+        for message in messages {
+            let bytes = rmp_serde::to_vec(&message).unwrap();
+            let received: ClientMessage = rmp_serde::from_slice(&bytes).unwrap();
+
+            assert_eq!(received, message);
+        }
+    }
+}
@@ -1,10 +1,71 @@
 pub const WORLD_WIDTH: usize = 1920;
 pub const WORLD_HEIGHT: usize = 1080;
 
-pub const BLOCKS_IN_ROW: usize = WORLD_WIDTH / (BLOCK_SIZE);
+pub const BLOCKS_IN_ROW: usize = blocks_in_row(WORLD_WIDTH, BLOCK_SIZE, BLOCK_GAP);
 pub const BLOCK_SIZE: usize = 50;
+pub const BLOCK_GAP: usize = 1;
 
 pub const PADDLE_WIDTH: usize = 200;
 pub const PADDLE_HEIGHT: usize = 20;
 
 pub const BALL_RADIUS: usize = 10;
+
+const _: () = assert!(
+    BLOCK_SIZE + BLOCK_GAP > 0,
+    "Blocks need a positive size for BLOCKS_IN_ROW"
+);
+const _: () = assert!(
+    PADDLE_WIDTH < WORLD_WIDTH,
+    "Paddles must fit inside the world"
+);
+const _: () = assert!(
+    BALL_RADIUS > 0 && WORLD_HEIGHT > 0,
+    "The world and the ball need a size"
+);
+
+pub const fn blocks_in_row(world_width: usize, block_size: usize, block_gap: usize) -> usize {
+    world_width / (block_size + block_gap)
+}
+
+pub const fn row_width(blocks_in_row: usize, block_size: usize, block_gap: usize) -> usize {
+    blocks_in_row * (block_size + block_gap) - block_gap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_row_fits_inside_the_world() {
+        let combinations = [
+            (BLOCK_SIZE, BLOCK_GAP),
+            (50, 0),
+            (50, 10),
+            (40, 1),
+            (64, 3),
+            (100, 20),
+            (959, 1),
+            (WORLD_WIDTH, 0),
+        ];
+
+        for (block_size, block_gap) in combinations {
+            let blocks = blocks_in_row(WORLD_WIDTH, block_size, block_gap);
+            let width = row_width(blocks, block_size, block_gap);
+
+            assert!(blocks > 0, "{} {}", block_size, block_gap);
+            assert!(width <= WORLD_WIDTH, "{} {}", block_size, block_gap);
+            assert!(
+                WORLD_WIDTH - width < block_size + block_gap,
+                "{} {}",
+                block_size,
+                block_gap
+            );
+        }
+    }
+
+    #[test]
+    fn default_row_matches_the_constants() {
+        assert_eq!(BLOCKS_IN_ROW, 37);
+        assert_eq!(row_width(BLOCKS_IN_ROW, BLOCK_SIZE, BLOCK_GAP), 1886);
+    }
+}
@@ -0,0 +1,3373 @@
+use crate::block_layouts::{self, BlockLayout};
+use crate::constants::{BLOCK_SIZE, PADDLE_HEIGHT, PADDLE_WIDTH, WORLD_HEIGHT, WORLD_WIDTH};
+use crate::match_settings::{DeflectionCurve, LaunchDirection, MatchSettings, PaddleSideHit};
+use crate::random::SeededRandom;
+use crate::world_data::{Ball, Block, Paddle, RegeneratingBlock, WorldData};
+use cgmath::{AbsDiffEq, InnerSpace, Vector2};
+use serde::{Deserialize, Serialize};
+use tracing::{info, trace_span};
+
+pub const BALL_SPEED: usize = 300;
+pub const SERVE_TIMER_SECONDS: usize = 10;
+pub const SERVE_COUNTDOWN_HINT_SECONDS: u8 = 3;
+pub const BALL_RESPAWN_DELAY_SECONDS: usize = 3;
+pub const RALLY_COUNTDOWN_HINT_SECONDS: u8 = 5;
+pub const MERCY_BALL_SPEED_FACTOR: f32 = 0.8;
+
+pub const PADDLE_SPEED: usize = 300;
+pub const PADDLE_SPIN_FACTOR: f32 = 0.5;
+pub const MAX_BALL_X_VELOCITY: f32 = 1.5;
+pub const PADDLE_COLLISION_COOLDOWN_TICKS: usize = 6;
+
+pub const GAME_LOOP_TIMESTEP_SECONDS: f32 = 1.0 / 60.0;
+
+pub const MAX_BALLS_PER_PLAYER: u8 = 5;
+const ATTACHED_BALLS_GAP: f32 = 4.0;
+const LAUNCH_CONE_HALF_ANGLE_DEGREES: f32 = 30.0;
+const CLAMPED_DEFLECTION_LIMIT: f32 = 0.6;
+
+pub const MAX_SPLIT_PADDLE_GAP: u32 = 200;
+pub const MAX_ARENA_EDGE_DEAD_ZONE: f32 = 50.0;
+
+pub struct PaddleSegment {
+    pub position: Vector2<f32>,
+    pub width: usize,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum GameMode {
+    Versus,
+    Practice,
+    SharedBall,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Versus, GameMode::Practice, GameMode::SharedBall];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Versus => "versus",
+            GameMode::Practice => "practice",
+            GameMode::SharedBall => "shared",
+        }
+    }
+
+    pub fn player_capacity(&self) -> u8 {
+        match self {
+            GameMode::Versus => 2,
+            GameMode::Practice => 1,
+            GameMode::SharedBall => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlayerAction {
+    MoveLeft,
+    MoveRight,
+    Launch,
+    TogglePause,
+    Ready,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerInput {
+    pub player_id: u8,
+    pub action: PlayerAction,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GameState {
+    pub world_data: WorldData,
+    pub game_mode: GameMode,
+    pub match_settings: MatchSettings,
+    respawn_timers: Vec<(u8, usize)>,
+    random: SeededRandom,
+    rally_ticks: usize,
+    paddle_speeds: [f32; 2],
+    level_blocks: Vec<Block>,
+    block_regeneration_ticks: Vec<(u32, usize)>,
+}
+
+pub fn create_world(
+    block_layout: BlockLayout,
+    game_mode: GameMode,
+    match_settings: MatchSettings,
+) -> GameState {
+    let world_data = create_world_data(block_layout, game_mode, &match_settings);
+    let level_blocks = world_data.blocks.clone();
+
+    let random = SeededRandom::new(match_settings.random_seed);
+
+    GameState {
+        world_data,
+        game_mode,
+        match_settings,
+        respawn_timers: vec![],
+        random,
+        rally_ticks: 0,
+        paddle_speeds: [0.0; 2],
+        level_blocks,
+        block_regeneration_ticks: vec![],
+    }
+}
+
+pub fn load_level(state: &mut GameState, block_layout: BlockLayout) {
+    state.world_data.blocks = create_level_blocks(block_layout);
+    state.world_data.regenerating_blocks.clear();
+    state.level_blocks = state.world_data.blocks.clone();
+    state.block_regeneration_ticks.clear();
+    state.world_data.layout_name = block_layout.name().to_string();
+
+    for paddle in state.world_data.paddles.iter_mut() {
+        paddle.respawn_countdown = None;
+    }
+
+    state.paddle_speeds = [0.0; 2];
+
+    state.world_data.balls = create_starting_balls(
+        &state.world_data.paddles,
+        state.game_mode,
+        &state.match_settings,
+    );
+
+    state.respawn_timers.clear();
+    state.rally_ticks = 0;
+    state.world_data.rally_countdown = None;
+}
+
+// Puts every free ball of the player into breakthrough for the configured duration. Returns the
+// number of balls that got it, which is zero when breakthrough is disabled in the match settings.
+pub fn grant_breakthrough(state: &mut GameState, player_id: u8) -> usize {
+    let Some(breakthrough_seconds) = state.match_settings.breakthrough_seconds else {
+        return 0;
+    };
+
+    let breakthrough_ticks = (breakthrough_seconds as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+    let mut granted_balls = 0;
+
+    for ball in state
+        .world_data
+        .balls
+        .iter_mut()
+        .filter(|b| b.is_free && b.owner == player_id)
+    {
+        ball.breakthrough_ticks = breakthrough_ticks;
+        granted_balls += 1;
+    }
+
+    granted_balls
+}
+
+pub fn step_world(state: &mut GameState, inputs: &[PlayerInput]) {
+    let ball_radius = state.match_settings.ball_radius;
+    let collision_margin = state.match_settings.collision_margin;
+
+    let respawn_delay_ticks =
+        (BALL_RESPAWN_DELAY_SECONDS as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+    let mut paddles: Vec<Paddle> = state.world_data.paddles.clone();
+    let mut balls: Vec<Ball> = state.world_data.balls.clone();
+
+    let mut held_directions = [0.0_f32; 2];
+    let mut requested_moves = [0.0_f32; 2];
+
+    let input_span = trace_span!("input_handling").entered();
+
+    for input in inputs {
+        if input.action == PlayerAction::TogglePause {
+            state.world_data.is_paused = !state.world_data.is_paused;
+            continue;
+        }
+
+        if state.world_data.is_paused {
+            continue;
+        }
+
+        if input.action == PlayerAction::Ready {
+            if let Some(ready_players) = state.world_data.ready_players.as_mut() {
+                ready_players[input.player_id as usize] = true;
+            }
+            continue;
+        }
+
+        let move_direction = match input.action {
+            PlayerAction::MoveLeft => -1.0,
+            PlayerAction::MoveRight => 1.0,
+            _ => 0.0,
+        };
+
+        if state.match_settings.paddle_acceleration.is_some() {
+            if move_direction != 0.0 {
+                held_directions[input.player_id as usize] = move_direction;
+            }
+        } else {
+            requested_moves[input.player_id as usize] += move_direction;
+        }
+
+        if input.action == PlayerAction::Launch && !is_launch_blocked(&state.world_data) {
+            let ball_index = balls.iter().position(|b| {
+                !b.is_free
+                    && (state.game_mode == GameMode::SharedBall || b.owner == input.player_id)
+            });
+
+            if let Some(ball_index) = ball_index {
+                let mut ball_to_move = balls[ball_index].clone();
+
+                if !ball_to_move.is_free {
+                    launch_ball(&mut ball_to_move, &state.match_settings, &mut state.random);
+                    balls[ball_index] = ball_to_move;
+                }
+            }
+        }
+    }
+
+    drop(input_span);
+
+    let max_paddle_delta = max_paddle_delta_per_tick(&state.match_settings);
+
+    for paddle in paddles.iter_mut() {
+        paddle.position.x +=
+            requested_moves[paddle.id as usize].clamp(-1.0, 1.0) * max_paddle_delta;
+    }
+
+    if state.world_data.is_paused {
+        return;
+    }
+
+    let serve_timer_ticks = (SERVE_TIMER_SECONDS as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+    let is_serve_allowed = !is_launch_blocked(&state.world_data);
+
+    for ball in balls.iter_mut().filter(|b| !b.is_free && is_serve_allowed) {
+        ball.attached_ticks += 1;
+
+        if ball.attached_ticks >= serve_timer_ticks {
+            launch_ball(ball, &state.match_settings, &mut state.random);
+            continue;
+        }
+
+        let remaining_seconds = ((serve_timer_ticks - ball.attached_ticks) as f32
+            * GAME_LOOP_TIMESTEP_SECONDS)
+            .ceil() as u8;
+
+        ball.serve_countdown = if remaining_seconds <= SERVE_COUNTDOWN_HINT_SECONDS {
+            Some(remaining_seconds)
+        } else {
+            None
+        };
+    }
+
+    if let Some(paddle_acceleration) = state.match_settings.paddle_acceleration {
+        let speed_step = paddle_acceleration * GAME_LOOP_TIMESTEP_SECONDS;
+        let max_speed = state.match_settings.paddle_speed;
+
+        for paddle in paddles.iter_mut() {
+            let held_direction = held_directions[paddle.id as usize];
+            let speed = &mut state.paddle_speeds[paddle.id as usize];
+
+            *speed = if held_direction != 0.0 {
+                (*speed + held_direction * speed_step).clamp(-max_speed, max_speed)
+            } else if speed.abs() <= speed_step {
+                0.0
+            } else {
+                *speed - speed.signum() * speed_step
+            };
+
+            paddle.position.x += *speed * GAME_LOOP_TIMESTEP_SECONDS;
+        }
+    }
+
+    let paddle_half_extent = paddle_half_extent(state.match_settings.split_paddle_gap);
+
+    for paddle in paddles.iter_mut() {
+        let mut is_clamped = false;
+
+        if paddle.position.x - paddle_half_extent <= 0.0 {
+            paddle.position.x = paddle_half_extent;
+            is_clamped = true;
+        }
+
+        if paddle.position.x + paddle_half_extent >= WORLD_WIDTH as f32 {
+            paddle.position.x = WORLD_WIDTH as f32 - paddle_half_extent;
+            is_clamped = true;
+        }
+
+        if is_clamped {
+            state.paddle_speeds[paddle.id as usize] = 0.0;
+        }
+    }
+
+    if state.match_settings.paddle_block_collision {
+        for paddle in paddles.iter_mut() {
+            let Some(previous_paddle) = state.world_data.paddles.iter().find(|p| p.id == paddle.id)
+            else {
+                continue;
+            };
+
+            if stop_paddle_at_blocks(
+                paddle,
+                previous_paddle.position.x,
+                &state.world_data.blocks,
+                state.match_settings.split_paddle_gap,
+            ) {
+                state.paddle_speeds[paddle.id as usize] = 0.0;
+            }
+        }
+    }
+
+    let paddle_velocities: Vec<f32> = paddles
+        .iter()
+        .zip(state.world_data.paddles.iter())
+        .map(|(paddle, previous_paddle)| {
+            (paddle.position.x - previous_paddle.position.x) / GAME_LOOP_TIMESTEP_SECONDS
+        })
+        .collect();
+
+    let collision_span = trace_span!("collision_detection").entered();
+
+    let arena_edge_dead_zone = state.match_settings.arena_edge_dead_zone;
+
+    for ball in balls.iter_mut() {
+        bounce_ball_off_side_walls(ball, ball_radius, arena_edge_dead_zone);
+
+        if state.game_mode == GameMode::Practice {
+            bounce_ball_off_top_wall(ball, ball_radius, arena_edge_dead_zone);
+        }
+    }
+
+    for ball in balls.iter_mut().filter(|b| !b.scoring) {
+        if let Some(conceding_player_id) = conceding_player_id(ball, ball_radius, state) {
+            let direction_y = match state.match_settings.launch_direction {
+                LaunchDirection::TowardConceder if conceding_player_id == 1 => -1.0,
+                _ => 1.0,
+            };
+
+            *ball = create_training_ball(ball.id, direction_y);
+        }
+    }
+
+    let lost_balls: Vec<(u8, u8, u8)> = balls
+        .iter()
+        .filter_map(|b| {
+            conceding_player_id(b, ball_radius, state)
+                .map(|conceding_player_id| (b.id, b.owner, conceding_player_id))
+        })
+        .collect();
+
+    balls.retain(|b| !lost_balls.iter().any(|(id, _, _)| *id == b.id));
+
+    let is_ball_lost = !lost_balls.is_empty();
+
+    for (_, lost_ball_owner, conceding_player_id) in lost_balls {
+        if state.game_mode == GameMode::SharedBall {
+            state
+                .respawn_timers
+                .push((conceding_player_id, respawn_delay_ticks));
+        } else {
+            state
+                .respawn_timers
+                .push((lost_ball_owner, respawn_delay_ticks));
+        }
+
+        if let Some(scores) = state.world_data.scores.as_mut() {
+            scores[1 - conceding_player_id as usize] += 1;
+        }
+
+        if let Some(survival_seconds) = state.world_data.survival_seconds.as_mut() {
+            *survival_seconds = 0.0;
+        }
+    }
+
+    for (player_id, remaining_ticks) in state.respawn_timers.iter_mut() {
+        *remaining_ticks = remaining_ticks.saturating_sub(1);
+
+        if let Some(paddle) = paddles.iter_mut().find(|p| p.id == *player_id) {
+            paddle.respawn_countdown = if *remaining_ticks == 0 {
+                None
+            } else {
+                Some((*remaining_ticks as f32 * GAME_LOOP_TIMESTEP_SECONDS).ceil() as u8)
+            };
+
+            if *remaining_ticks == 0 {
+                balls.push(create_attached_ball(
+                    next_free_ball_id(&balls),
+                    paddle,
+                    ball_radius,
+                    0.0,
+                ));
+            }
+        }
+    }
+
+    state
+        .respawn_timers
+        .retain(|(_, remaining_ticks)| *remaining_ticks > 0);
+
+    let mut blocks: Vec<Block> = state.world_data.blocks.clone();
+
+    let segments: Vec<(PaddleSegment, f32)> = paddles
+        .iter()
+        .zip(paddle_velocities.iter().copied())
+        .flat_map(|(paddle, paddle_velocity)| {
+            paddle_segments(paddle, state.match_settings.split_paddle_gap)
+                .into_iter()
+                .map(move |segment| (segment, paddle_velocity))
+        })
+        .collect();
+
+    let mut block_hits: Vec<Option<usize>> = balls
+        .iter()
+        .map(|ball| {
+            if ball.breakthrough_ticks > 0 {
+                return None;
+            }
+
+            blocks.iter().position(|block| {
+                is_ball_collided_with_object(
+                    ball,
+                    ball_radius,
+                    collision_margin,
+                    block.position,
+                    BLOCK_SIZE,
+                    BLOCK_SIZE,
+                )
+            })
+        })
+        .collect();
+
+    for (ball, block_hit) in balls.iter_mut().zip(block_hits.iter_mut()) {
+        if ball.paddle_collision_cooldown_ticks > 0 {
+            ball.paddle_collision_cooldown_ticks -= 1;
+            continue;
+        }
+
+        for (segment, paddle_velocity) in segments.iter() {
+            if is_ball_collided_with_object(
+                ball,
+                ball_radius,
+                collision_margin,
+                segment.position,
+                segment.width,
+                PADDLE_HEIGHT,
+            ) {
+                if let Some(block_index) = *block_hit {
+                    let paddle_penetration = ball_penetration_depth(
+                        ball,
+                        ball_radius,
+                        collision_margin,
+                        segment.position,
+                        segment.width,
+                        PADDLE_HEIGHT,
+                    );
+
+                    let block_penetration = ball_penetration_depth(
+                        ball,
+                        ball_radius,
+                        collision_margin,
+                        blocks[block_index].position,
+                        BLOCK_SIZE,
+                        BLOCK_SIZE,
+                    );
+
+                    if block_penetration > paddle_penetration {
+                        break;
+                    }
+                }
+
+                if is_ball_hit_side_of_object(
+                    ball,
+                    ball_radius,
+                    collision_margin,
+                    segment.position,
+                    segment.width,
+                    PADDLE_HEIGHT,
+                ) {
+                    if state.match_settings.paddle_side_hit == PaddleSideHit::PassThrough {
+                        break;
+                    }
+
+                    *block_hit = None;
+
+                    let direction_x = (ball.position.x - segment.position.x).signum();
+
+                    ball.position.x = (segment.position.x
+                        + direction_x * (segment.width as f32 / 2.0 + ball_radius))
+                        .clamp(ball_radius, WORLD_WIDTH as f32 - ball_radius);
+                    ball.velocity.x = direction_x * ball.velocity.x.abs();
+                    ball.paddle_collision_cooldown_ticks = PADDLE_COLLISION_COOLDOWN_TICKS;
+
+                    break;
+                }
+
+                *block_hit = None;
+
+                let paddle_center = segment.position.x;
+                let ball_center = ball.position.x;
+                let centers_difference = ball_center - paddle_center;
+
+                if !centers_difference.abs_diff_eq(&0.0, f32::EPSILON) {
+                    let deflect_factor = centers_difference / (segment.width as f32 / 2.0);
+                    ball.velocity.x = apply_deflection_curve(
+                        state.match_settings.deflection_curve,
+                        deflect_factor,
+                    );
+                }
+
+                ball.velocity.x +=
+                    *paddle_velocity / state.match_settings.paddle_speed * PADDLE_SPIN_FACTOR;
+
+                if state.match_settings.bounce_jitter > 0.0 {
+                    ball.velocity.x +=
+                        state.random.next_signed_unit() * state.match_settings.bounce_jitter;
+                }
+
+                ball.velocity.x = ball
+                    .velocity
+                    .x
+                    .clamp(-MAX_BALL_X_VELOCITY, MAX_BALL_X_VELOCITY);
+
+                let ball_offset_from_paddle = PADDLE_HEIGHT as f32 / 2.0 + ball_radius;
+
+                if ball.position.y < segment.position.y {
+                    ball.position.y = segment.position.y - ball_offset_from_paddle;
+                    ball.velocity.y = -1.0;
+                } else {
+                    ball.position.y = segment.position.y + ball_offset_from_paddle;
+                    ball.velocity.y = 1.0;
+                }
+
+                if state.match_settings.gravity == 0.0 {
+                    ball.velocity = ball.velocity.normalize();
+                }
+
+                ball.paddle_collision_cooldown_ticks = PADDLE_COLLISION_COOLDOWN_TICKS;
+
+                break;
+            }
+        }
+    }
+
+    credit_block_hits_to_closest_ball(&balls, &mut block_hits, &blocks);
+
+    let game_mode = state.game_mode;
+
+    for (ball, block_hit) in balls.iter_mut().zip(block_hits) {
+        if let Some(block_index) = block_hit {
+            let block = &mut blocks[block_index];
+
+            if is_ball_hit_top_or_bottom_of_block(ball, block) {
+                ball.velocity.y *= -1.0;
+            } else {
+                ball.velocity.x *= -1.0;
+            }
+
+            block.hits_life -= 1;
+
+            if block.hits_life == 0 {
+                credit_destroyed_block(&mut state.world_data.scores, game_mode, ball);
+            }
+        }
+    }
+
+    // A breakthrough ball never reflects off blocks, it wears down every block it overlaps on
+    // each tick until its timer runs out.
+    for ball in balls
+        .iter_mut()
+        .filter(|b| b.is_free && b.breakthrough_ticks > 0)
+    {
+        ball.breakthrough_ticks -= 1;
+
+        for block in blocks.iter_mut().filter(|b| b.hits_life > 0) {
+            if is_ball_collided_with_object(
+                ball,
+                ball_radius,
+                collision_margin,
+                block.position,
+                BLOCK_SIZE,
+                BLOCK_SIZE,
+            ) {
+                block.hits_life -= 1;
+
+                if block.hits_life == 0 {
+                    credit_destroyed_block(&mut state.world_data.scores, game_mode, ball);
+                }
+            }
+        }
+    }
+
+    if state.match_settings.gravity == 0.0 {
+        for ball in balls.iter_mut().filter(|b| b.is_free) {
+            if ball.velocity.magnitude2() > 0.0 {
+                ball.velocity = ball.velocity.normalize();
+            }
+        }
+    }
+
+    drop(collision_span);
+
+    let blocks_count = blocks.len();
+
+    if state.match_settings.block_regeneration_seconds.is_some() {
+        state.block_regeneration_ticks.extend(
+            blocks
+                .iter()
+                .filter(|b| b.hits_life == 0)
+                .map(|b| (b.id, 0)),
+        );
+    }
+
+    trace_span!("block_updates").in_scope(|| blocks.retain(|b| b.hits_life != 0));
+
+    let is_block_destroyed = blocks.len() < blocks_count;
+
+    if let Some(block_regeneration_seconds) = state.match_settings.block_regeneration_seconds {
+        regenerate_blocks(
+            state,
+            &mut blocks,
+            &balls,
+            &paddles,
+            block_regeneration_seconds,
+        );
+    }
+
+    if let (Some(mercy_score_gap), Some(scores)) = (
+        state.match_settings.mercy_score_gap,
+        state.world_data.scores,
+    ) {
+        state.world_data.mercy_player = if scores[0] > scores[1] + mercy_score_gap {
+            Some(1)
+        } else if scores[1] > scores[0] + mercy_score_gap {
+            Some(0)
+        } else {
+            None
+        };
+    }
+
+    for ball in balls.iter_mut() {
+        if ball.is_free {
+            let speed_factor = if state
+                .world_data
+                .mercy_player
+                .is_some_and(|player_id| is_in_player_half(ball.position, player_id))
+            {
+                MERCY_BALL_SPEED_FACTOR
+            } else {
+                1.0
+            };
+
+            ball.velocity.y +=
+                state.match_settings.gravity / BALL_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
+            ball.position +=
+                ball.velocity * BALL_SPEED as f32 * speed_factor * GAME_LOOP_TIMESTEP_SECONDS;
+        }
+    }
+
+    if let Some(max_rally_seconds) = state.match_settings.max_rally_seconds {
+        let max_rally_ticks = (max_rally_seconds as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+        let is_rally_running = balls.iter().any(|b| b.is_free && b.scoring);
+
+        if is_ball_lost || is_block_destroyed || !is_rally_running {
+            state.rally_ticks = 0;
+        } else {
+            state.rally_ticks += 1;
+        }
+
+        if state.rally_ticks >= max_rally_ticks {
+            info!(
+                "Rally stalemate after {} seconds, resetting balls to serve",
+                max_rally_seconds
+            );
+
+            for ball in balls.iter_mut().filter(|b| b.is_free && b.scoring) {
+                if let Some(paddle) = paddles.iter().find(|p| p.id == ball.owner) {
+                    *ball = create_attached_ball(ball.id, paddle, ball_radius, 0.0);
+                }
+            }
+
+            state.rally_ticks = 0;
+        }
+
+        let remaining_seconds = ((max_rally_ticks - state.rally_ticks) as f32
+            * GAME_LOOP_TIMESTEP_SECONDS)
+            .ceil() as u8;
+
+        state.world_data.rally_countdown =
+            if state.rally_ticks > 0 && remaining_seconds <= RALLY_COUNTDOWN_HINT_SECONDS {
+                Some(remaining_seconds)
+            } else {
+                None
+            };
+    }
+
+    state.world_data.blocks = blocks;
+    state.world_data.paddles = paddles;
+    state.world_data.balls = balls;
+    state.world_data.tick += 1;
+
+    if let Some(survival_seconds) = state.world_data.survival_seconds.as_mut() {
+        if state
+            .world_data
+            .balls
+            .iter()
+            .any(|b| b.is_free && b.scoring)
+        {
+            *survival_seconds += GAME_LOOP_TIMESTEP_SECONDS;
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    assert_world_invariants(state);
+}
+
+/// Checks, in debug builds only, that after a step:
+/// - no ball or paddle position or velocity is NaN or infinite
+/// - every ball is inside the arena, allowing one tick of movement past a wall or goal line
+/// - every paddle is within its clamp range
+/// - every free ball has unit speed when gravity is off
+/// - blocks are ordered by strictly increasing id
+#[cfg(debug_assertions)]
+fn assert_world_invariants(state: &GameState) {
+    let ball_radius = state.match_settings.ball_radius;
+    let paddle_half_extent = paddle_half_extent(state.match_settings.split_paddle_gap);
+    let tick = state.world_data.tick;
+
+    for ball in &state.world_data.balls {
+        assert!(
+            ball.position.x.is_finite()
+                && ball.position.y.is_finite()
+                && ball.velocity.x.is_finite()
+                && ball.velocity.y.is_finite(),
+            "Ball {} has a non-finite position or velocity at tick {}: {:?}",
+            ball.id,
+            tick,
+            ball
+        );
+
+        let step = ball.velocity.magnitude() * BALL_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS
+            + f32::EPSILON * WORLD_WIDTH as f32;
+
+        assert!(
+            ball.position.x >= ball_radius - step
+                && ball.position.x <= WORLD_WIDTH as f32 - ball_radius + step
+                && ball.position.y >= -step
+                && ball.position.y <= WORLD_HEIGHT as f32 - ball_radius + step,
+            "Ball {} left the arena at tick {}: {:?}",
+            ball.id,
+            tick,
+            ball.position
+        );
+
+        if ball.is_free && state.match_settings.gravity == 0.0 {
+            assert!(
+                (ball.velocity.magnitude() - 1.0).abs() < 1e-3,
+                "Ball {} has speed {} instead of 1 at tick {}",
+                ball.id,
+                ball.velocity.magnitude(),
+                tick
+            );
+        }
+    }
+
+    for paddle in &state.world_data.paddles {
+        assert!(
+            paddle.position.x.is_finite() && paddle.position.y.is_finite(),
+            "Paddle {} has a non-finite position at tick {}: {:?}",
+            paddle.id,
+            tick,
+            paddle.position
+        );
+
+        assert!(
+            paddle.position.x >= paddle_half_extent
+                && paddle.position.x <= WORLD_WIDTH as f32 - paddle_half_extent,
+            "Paddle {} is outside its clamp range at tick {}: {}",
+            paddle.id,
+            tick,
+            paddle.position.x
+        );
+    }
+
+    assert!(
+        state
+            .world_data
+            .blocks
+            .windows(2)
+            .all(|pair| pair[0].id < pair[1].id),
+        "Blocks are not ordered by id at tick {}",
+        tick
+    );
+}
+
+/// The furthest a paddle may travel in one tick. The server applies it however many move inputs
+/// arrive in a tick, and client-side prediction should use the same value to stay in step.
+pub fn max_paddle_delta_per_tick(match_settings: &MatchSettings) -> f32 {
+    match_settings.paddle_speed * GAME_LOOP_TIMESTEP_SECONDS
+}
+
+/// Returns the collidable parts of a paddle. A split paddle is two half-width segments that move
+/// together, with `split_paddle_gap` world units between them around the paddle position.
+pub fn paddle_segments(paddle: &Paddle, split_paddle_gap: Option<u32>) -> Vec<PaddleSegment> {
+    match split_paddle_gap {
+        Some(gap) => {
+            let segment_width = PADDLE_WIDTH / 2;
+            let segment_offset = gap as f32 / 2.0 + segment_width as f32 / 2.0;
+
+            vec![
+                PaddleSegment {
+                    position: paddle.position - Vector2::new(segment_offset, 0.0),
+                    width: segment_width,
+                },
+                PaddleSegment {
+                    position: paddle.position + Vector2::new(segment_offset, 0.0),
+                    width: segment_width,
+                },
+            ]
+        }
+        None => vec![PaddleSegment {
+            position: paddle.position,
+            width: PADDLE_WIDTH,
+        }],
+    }
+}
+
+/// Moves a paddle back along its direction of travel until none of its segments overlap a block.
+/// Returns whether the paddle was stopped. A paddle that didn't move is left alone.
+fn stop_paddle_at_blocks(
+    paddle: &mut Paddle,
+    previous_x: f32,
+    blocks: &[Block],
+    split_paddle_gap: Option<u32>,
+) -> bool {
+    if paddle.position.x == previous_x {
+        return false;
+    }
+
+    let move_direction = (paddle.position.x - previous_x).signum();
+
+    let mut is_stopped = false;
+
+    for segment in paddle_segments(paddle, split_paddle_gap) {
+        let segment_offset = segment.position.x - paddle.position.x;
+
+        for block in blocks {
+            let is_overlapping_vertically = (segment.position.y - block.position.y).abs()
+                < (PADDLE_HEIGHT + BLOCK_SIZE) as f32 / 2.0;
+            let is_overlapping_horizontally =
+                (paddle.position.x + segment_offset - block.position.x).abs()
+                    < (segment.width + BLOCK_SIZE) as f32 / 2.0;
+
+            if !is_overlapping_vertically || !is_overlapping_horizontally {
+                continue;
+            }
+
+            let touching_x = block.position.x
+                - move_direction * (segment.width + BLOCK_SIZE) as f32 / 2.0
+                - segment_offset;
+
+            paddle.position.x = if move_direction > 0.0 {
+                paddle.position.x.min(touching_x.max(previous_x))
+            } else {
+                paddle.position.x.max(touching_x.min(previous_x))
+            };
+
+            is_stopped = true;
+        }
+    }
+
+    is_stopped
+}
+
+pub fn paddle_half_extent(split_paddle_gap: Option<u32>) -> f32 {
+    (PADDLE_WIDTH as f32 + split_paddle_gap.unwrap_or(0) as f32) / 2.0
+}
+
+fn create_world_data(
+    block_layout: BlockLayout,
+    game_mode: GameMode,
+    match_settings: &MatchSettings,
+) -> WorldData {
+    let blocks = create_level_blocks(block_layout);
+
+    let mut paddles: Vec<Paddle> = Vec::from([
+        Paddle {
+            id: 1,
+            position: Vector2::new(WORLD_WIDTH as f32 / 2.0, match_settings.paddle_offsets[1]),
+            respawn_countdown: None,
+        },
+        Paddle {
+            id: 0,
+            position: Vector2::new(
+                WORLD_WIDTH as f32 / 2.0,
+                WORLD_HEIGHT as f32 - match_settings.paddle_offsets[0],
+            ),
+            respawn_countdown: None,
+        },
+    ]);
+
+    if game_mode == GameMode::Practice {
+        paddles.retain(|p| p.id == 0);
+    }
+
+    let balls = create_starting_balls(&paddles, game_mode, match_settings);
+
+    WorldData {
+        blocks,
+        regenerating_blocks: vec![],
+        paddles,
+        balls,
+        layout_name: block_layout.name().to_string(),
+        level_index: None,
+        tick: 0,
+        is_paused: false,
+        survival_seconds: match game_mode {
+            GameMode::Versus | GameMode::SharedBall => None,
+            GameMode::Practice => Some(0.0),
+        },
+        waiting_for_players: None,
+        rally_countdown: None,
+        match_countdown: None,
+        reconnect_grace_seconds: None,
+        ready_players: if match_settings.require_ready {
+            Some([false, game_mode.player_capacity() < 2])
+        } else {
+            None
+        },
+        scores: match game_mode {
+            GameMode::Versus | GameMode::SharedBall => Some([0, 0]),
+            GameMode::Practice => None,
+        },
+        mercy_player: None,
+    }
+}
+
+fn create_level_blocks(block_layout: BlockLayout) -> Vec<Block> {
+    let mut blocks = block_layouts::create_blocks(block_layout);
+
+    for (id, block) in blocks.iter_mut().enumerate() {
+        block.id = id as u32;
+    }
+
+    blocks
+}
+
+fn create_starting_balls(
+    paddles: &[Paddle],
+    game_mode: GameMode,
+    match_settings: &MatchSettings,
+) -> Vec<Ball> {
+    let balls_per_paddle = match game_mode {
+        GameMode::SharedBall => 1,
+        GameMode::Versus | GameMode::Practice => match_settings.balls_per_player,
+    };
+
+    let attached_ball_spacing = match_settings.ball_radius * 2.0 + ATTACHED_BALLS_GAP;
+
+    let mut balls: Vec<Ball> = vec![];
+
+    for paddle in paddles.iter().filter(|p| match game_mode {
+        GameMode::SharedBall => p.id == 0,
+        GameMode::Versus | GameMode::Practice => true,
+    }) {
+        for slot in 0..balls_per_paddle {
+            let offset_x =
+                (slot as f32 - (balls_per_paddle - 1) as f32 / 2.0) * attached_ball_spacing;
+
+            balls.push(create_attached_ball(
+                balls.len() as u8,
+                paddle,
+                match_settings.ball_radius,
+                offset_x,
+            ));
+        }
+    }
+
+    if match_settings.training_ball {
+        balls.push(create_training_ball(balls.len() as u8, 1.0));
+    }
+
+    balls
+}
+
+fn next_free_ball_id(balls: &[Ball]) -> u8 {
+    balls
+        .iter()
+        .map(|b| b.id.wrapping_add(1))
+        .max()
+        .unwrap_or(0)
+}
+
+fn conceding_player_id(ball: &Ball, ball_radius: f32, state: &GameState) -> Option<u8> {
+    let goal_line_inset = state.match_settings.goal_line.inset_at(ball.position.x);
+
+    if ball.position.y + ball_radius >= WORLD_HEIGHT as f32 - goal_line_inset {
+        Some(0)
+    } else if ball.position.y - ball_radius <= goal_line_inset
+        && state.game_mode != GameMode::Practice
+    {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+fn apply_deflection_curve(curve: DeflectionCurve, deflect_factor: f32) -> f32 {
+    match curve {
+        DeflectionCurve::Linear => deflect_factor,
+        DeflectionCurve::Quadratic => deflect_factor * deflect_factor.abs(),
+        DeflectionCurve::Clamped => {
+            deflect_factor.clamp(-CLAMPED_DEFLECTION_LIMIT, CLAMPED_DEFLECTION_LIMIT)
+        }
+    }
+}
+
+fn is_in_player_half(position: Vector2<f32>, player_id: u8) -> bool {
+    let is_bottom_half = position.y >= WORLD_HEIGHT as f32 / 2.0;
+
+    if player_id == 0 {
+        is_bottom_half
+    } else {
+        !is_bottom_half
+    }
+}
+
+fn is_waiting_for_ready(world_data: &WorldData) -> bool {
+    world_data
+        .ready_players
+        .is_some_and(|ready_players| ready_players.contains(&false))
+}
+
+fn is_launch_blocked(world_data: &WorldData) -> bool {
+    is_waiting_for_ready(world_data) || world_data.match_countdown.is_some()
+}
+
+fn create_attached_ball(id: u8, paddle: &Paddle, ball_radius: f32, offset_x: f32) -> Ball {
+    let ball_offset_from_paddle = PADDLE_HEIGHT as f32 / 2.0 + ball_radius;
+
+    let ball_position_y = if paddle.position.y < WORLD_HEIGHT as f32 / 2.0 {
+        paddle.position.y + ball_offset_from_paddle
+    } else {
+        paddle.position.y - ball_offset_from_paddle
+    };
+
+    Ball {
+        id,
+        owner: paddle.id,
+        position: Vector2::new(paddle.position.x + offset_x, ball_position_y),
+        velocity: Vector2::new(0.0, 0.0),
+        is_free: false,
+        scoring: true,
+        attached_ticks: 0,
+        serve_countdown: None,
+        breakthrough_ticks: 0,
+        paddle_collision_cooldown_ticks: 0,
+    }
+}
+
+fn create_training_ball(id: u8, direction_y: f32) -> Ball {
+    Ball {
+        id,
+        owner: 0,
+        position: Vector2::new(WORLD_WIDTH as f32 / 2.0, WORLD_HEIGHT as f32 / 2.0),
+        velocity: Vector2::new(0.5, direction_y).normalize(),
+        is_free: true,
+        scoring: false,
+        attached_ticks: 0,
+        serve_countdown: None,
+        breakthrough_ticks: 0,
+        paddle_collision_cooldown_ticks: 0,
+    }
+}
+
+/// Launches a ball away from the half it sits in, so it always heads into the playfield. In the
+/// cone mode the angle from vertical is picked by the seeded random within the cone.
+fn launch_ball(ball: &mut Ball, match_settings: &MatchSettings, random: &mut SeededRandom) {
+    let direction_y = if ball.position.y < WORLD_HEIGHT as f32 / 2.0 {
+        1.0
+    } else {
+        -1.0
+    };
+
+    let angle = match match_settings.launch_direction {
+        LaunchDirection::Cone => {
+            random.next_signed_unit() * LAUNCH_CONE_HALF_ANGLE_DEGREES.to_radians()
+        }
+        LaunchDirection::Straight | LaunchDirection::TowardConceder => 0.0,
+    };
+
+    ball.velocity = Vector2::new(angle.sin(), direction_y * angle.cos());
+    ball.is_free = true;
+    ball.attached_ticks = 0;
+    ball.serve_countdown = None;
+}
+
+fn bounce_ball_off_side_walls(ball: &mut Ball, ball_radius: f32, arena_edge_dead_zone: f32) {
+    let left_wall_position = ball_radius + arena_edge_dead_zone;
+    let right_wall_position = WORLD_WIDTH as f32 - ball_radius - arena_edge_dead_zone;
+
+    if ball.position.x <= left_wall_position {
+        ball.position.x = left_wall_position;
+        ball.velocity.x = ball.velocity.x.abs();
+    } else if ball.position.x >= right_wall_position {
+        ball.position.x = right_wall_position;
+        ball.velocity.x = -ball.velocity.x.abs();
+    }
+}
+
+fn bounce_ball_off_top_wall(ball: &mut Ball, ball_radius: f32, arena_edge_dead_zone: f32) {
+    let top_wall_position = ball_radius + arena_edge_dead_zone;
+
+    if ball.position.y <= top_wall_position {
+        ball.position.y = top_wall_position;
+        ball.velocity.y = ball.velocity.y.abs();
+    }
+}
+
+fn is_ball_collided_with_object(
+    ball: &Ball,
+    ball_radius: f32,
+    collision_margin: f32,
+    position: Vector2<f32>,
+    width: usize,
+    height: usize,
+) -> bool {
+    let ball_extent = ball_radius + collision_margin;
+
+    let ball_left = ball.position.x - ball_extent;
+    let ball_right = ball.position.x + ball_extent;
+    let ball_top = ball.position.y - ball_extent;
+    let ball_bottom = ball.position.y + ball_extent;
+
+    let object_left = position.x - (width as f32 / 2.0);
+    let object_right = position.x + (width as f32 / 2.0);
+    let object_top = position.y - (height as f32 / 2.0);
+    let object_bottom = position.y + (height as f32 / 2.0);
+
+    ball_left < object_right
+        && ball_right > object_left
+        && ball_top < object_bottom
+        && ball_bottom > object_top
+}
+
+fn ball_penetration_depth(
+    ball: &Ball,
+    ball_radius: f32,
+    collision_margin: f32,
+    position: Vector2<f32>,
+    width: usize,
+    height: usize,
+) -> f32 {
+    let ball_extent = ball_radius + collision_margin;
+
+    let overlap_x = ball_extent + width as f32 / 2.0 - (ball.position.x - position.x).abs();
+    let overlap_y = ball_extent + height as f32 / 2.0 - (ball.position.y - position.y).abs();
+
+    overlap_x.min(overlap_y)
+}
+
+fn is_ball_hit_side_of_object(
+    ball: &Ball,
+    ball_radius: f32,
+    collision_margin: f32,
+    position: Vector2<f32>,
+    width: usize,
+    height: usize,
+) -> bool {
+    let ball_extent = ball_radius + collision_margin;
+
+    let overlap_x = ball_extent + width as f32 / 2.0 - (ball.position.x - position.x).abs();
+    let overlap_y = ball_extent + height as f32 / 2.0 - (ball.position.y - position.y).abs();
+
+    overlap_x < overlap_y
+}
+
+/// Counts down the regeneration of destroyed blocks and puts each one back at its original
+/// position with full `hits_life` once its delay has passed and no ball or paddle is in the way.
+fn regenerate_blocks(
+    state: &mut GameState,
+    blocks: &mut Vec<Block>,
+    balls: &[Ball],
+    paddles: &[Paddle],
+    block_regeneration_seconds: u32,
+) {
+    let regeneration_ticks =
+        (block_regeneration_seconds as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+    let ball_radius = state.match_settings.ball_radius;
+    let collision_margin = state.match_settings.collision_margin;
+    let split_paddle_gap = state.match_settings.split_paddle_gap;
+
+    let level_blocks = &state.level_blocks;
+
+    state
+        .block_regeneration_ticks
+        .retain_mut(|(block_id, elapsed_ticks)| {
+            *elapsed_ticks += 1;
+
+            if *elapsed_ticks < regeneration_ticks {
+                return true;
+            }
+
+            let Some(level_block) = level_blocks.iter().find(|b| b.id == *block_id) else {
+                return false;
+            };
+
+            let is_occupied_by_ball = balls.iter().any(|ball| {
+                is_ball_collided_with_object(
+                    ball,
+                    ball_radius,
+                    collision_margin,
+                    level_block.position,
+                    BLOCK_SIZE,
+                    BLOCK_SIZE,
+                )
+            });
+
+            let is_occupied_by_paddle = paddles
+                .iter()
+                .flat_map(|paddle| paddle_segments(paddle, split_paddle_gap))
+                .any(|segment| {
+                    (segment.position.x - level_block.position.x).abs()
+                        < (segment.width + BLOCK_SIZE) as f32 / 2.0
+                        && (segment.position.y - level_block.position.y).abs()
+                            < (PADDLE_HEIGHT + BLOCK_SIZE) as f32 / 2.0
+                });
+
+            if is_occupied_by_ball || is_occupied_by_paddle {
+                return true;
+            }
+
+            // Blocks are kept ordered by id, so a regenerated block goes back into its slot
+            // instead of the end and snapshots of the same field always serialize the same way.
+            let index = blocks.partition_point(|block| block.id < level_block.id);
+            blocks.insert(index, level_block.clone());
+            false
+        });
+
+    state.world_data.regenerating_blocks = state
+        .block_regeneration_ticks
+        .iter()
+        .filter_map(|(block_id, elapsed_ticks)| {
+            level_blocks
+                .iter()
+                .find(|b| b.id == *block_id)
+                .map(|level_block| RegeneratingBlock {
+                    id: *block_id,
+                    position: level_block.position,
+                    progress: (*elapsed_ticks as f32 / regeneration_ticks as f32).min(1.0),
+                })
+        })
+        .collect();
+}
+
+/// When several balls overlap the same block in one tick, only the ball whose center is closest
+/// to the block center hits it, with the lower ball id winning exact ties. The other balls keep
+/// flying this tick, so the outcome doesn't depend on the order of `balls`.
+fn credit_block_hits_to_closest_ball(
+    balls: &[Ball],
+    block_hits: &mut [Option<usize>],
+    blocks: &[Block],
+) {
+    let mut hit_blocks: Vec<usize> = block_hits.iter().flatten().copied().collect();
+    hit_blocks.sort_unstable();
+    hit_blocks.dedup();
+
+    for block_index in hit_blocks {
+        let block_center = blocks[block_index].position;
+
+        let credited_ball_index = (0..balls.len())
+            .filter(|&index| block_hits[index] == Some(block_index))
+            .min_by(|&a, &b| {
+                let distance_a = (balls[a].position - block_center).magnitude2();
+                let distance_b = (balls[b].position - block_center).magnitude2();
+
+                distance_a
+                    .total_cmp(&distance_b)
+                    .then(balls[a].id.cmp(&balls[b].id))
+            });
+
+        for (index, block_hit) in block_hits.iter_mut().enumerate() {
+            if *block_hit == Some(block_index) && Some(index) != credited_ball_index {
+                *block_hit = None;
+            }
+        }
+    }
+}
+
+/// In versus mode every block destroyed by a scoring ball is worth a point to the ball's owner.
+fn credit_destroyed_block(scores: &mut Option<[u32; 2]>, game_mode: GameMode, ball: &Ball) {
+    if game_mode != GameMode::Versus || !ball.scoring {
+        return;
+    }
+
+    if let Some(scores) = scores.as_mut() {
+        scores[ball.owner as usize] += 1;
+    }
+}
+
+fn is_ball_hit_top_or_bottom_of_block(ball: &Ball, block: &Block) -> bool {
+    let vector_from_block_to_ball = ball.position - block.position;
+
+    vector_from_block_to_ball.y.abs() > vector_from_block_to_ball.x.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_empty_world(game_mode: GameMode, match_settings: MatchSettings) -> GameState {
+        let mut state = create_world(BlockLayout::Grid, game_mode, match_settings);
+        state.world_data.blocks.clear();
+        state.level_blocks.clear();
+        state
+    }
+
+    fn create_free_ball(id: u8, position: Vector2<f32>, velocity: Vector2<f32>) -> Ball {
+        Ball {
+            id,
+            owner: 0,
+            position,
+            velocity: velocity.normalize(),
+            is_free: true,
+            scoring: true,
+            attached_ticks: 0,
+            serve_countdown: None,
+            breakthrough_ticks: 0,
+            paddle_collision_cooldown_ticks: 0,
+        }
+    }
+
+    fn paddle_position(state: &GameState, player_id: u8) -> Vector2<f32> {
+        state
+            .world_data
+            .paddles
+            .iter()
+            .find(|p| p.id == player_id)
+            .unwrap()
+            .position
+    }
+
+    fn input(player_id: u8, action: PlayerAction) -> PlayerInput {
+        PlayerInput { player_id, action }
+    }
+
+    fn count_sign_changes(values: &[f32]) -> usize {
+        values
+            .windows(2)
+            .filter(|pair| pair[0].signum() != pair[1].signum())
+            .count()
+    }
+
+    #[test]
+    fn paddle_moving_right_spins_centered_hit_to_the_right() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let paddle = paddle_position(&state, 0);
+        let paddle_delta = max_paddle_delta_per_tick(&state.match_settings);
+
+        let ball_y = paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 2.0;
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(paddle.x + paddle_delta, ball_y),
+            Vector2::new(0.0, 1.0),
+        )];
+
+        step_world(&mut state, &[input(0, PlayerAction::MoveRight)]);
+
+        let ball = &state.world_data.balls[0];
+        assert!(ball.velocity.x > 0.0, "{:?}", ball.velocity);
+        assert!(ball.velocity.y < 0.0, "{:?}", ball.velocity);
+        assert!((ball.velocity.magnitude() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn still_paddle_keeps_centered_hit_vertical() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let paddle = paddle_position(&state, 0);
+
+        let ball_y = paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 2.0;
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(paddle.x, ball_y),
+            Vector2::new(0.0, 1.0),
+        )];
+
+        step_world(&mut state, &[]);
+
+        let ball = &state.world_data.balls[0];
+        assert_eq!(ball.velocity, Vector2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn shallow_ball_bounces_off_side_wall_exactly_once() {
+        let match_settings = MatchSettings {
+            arena_edge_dead_zone: 5.0,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(state.match_settings.ball_radius + 5.1, 100.0),
+            Vector2::new(-0.02, 1.0),
+        )];
+
+        let mut velocities_x = vec![state.world_data.balls[0].velocity.x];
+
+        for _ in 0..60 {
+            step_world(&mut state, &[]);
+
+            velocities_x.push(state.world_data.balls[0].velocity.x);
+        }
+
+        assert_eq!(count_sign_changes(&velocities_x), 1, "{:?}", velocities_x);
+        assert!(state.world_data.balls[0].velocity.x > 0.0);
+    }
+
+    #[test]
+    fn shallow_ball_bounces_off_top_wall_exactly_once() {
+        let match_settings = MatchSettings {
+            arena_edge_dead_zone: 5.0,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Practice, match_settings);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(500.0, state.match_settings.ball_radius + 5.1),
+            Vector2::new(1.0, -0.02),
+        )];
+
+        let mut velocities_y = vec![state.world_data.balls[0].velocity.y];
+
+        for _ in 0..60 {
+            step_world(&mut state, &[]);
+
+            velocities_y.push(state.world_data.balls[0].velocity.y);
+        }
+
+        assert_eq!(count_sign_changes(&velocities_y), 1, "{:?}", velocities_y);
+        assert!(state.world_data.balls[0].velocity.y > 0.0);
+    }
+
+    #[test]
+    fn ball_overlapping_paddle_for_several_ticks_reflects_once() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let paddle = paddle_position(&state, 0);
+
+        let overlapping_position = Vector2::new(
+            paddle.x + 30.0,
+            paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 4.0,
+        );
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            overlapping_position,
+            Vector2::new(0.0, 1.0),
+        )];
+
+        let mut reflections = 0;
+
+        for _ in 0..=PADDLE_COLLISION_COOLDOWN_TICKS {
+            state.world_data.balls[0].position = overlapping_position;
+            state.world_data.balls[0].velocity = Vector2::new(0.0, 1.0);
+
+            step_world(&mut state, &[]);
+
+            if state.world_data.balls[0].velocity.y < 0.0 {
+                reflections += 1;
+            }
+        }
+
+        assert_eq!(reflections, 1);
+    }
+
+    #[test]
+    fn lost_ball_reappears_on_paddle_after_respawn_delay() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let respawn_delay_ticks =
+            (BALL_RESPAWN_DELAY_SECONDS as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(
+                100.0,
+                WORLD_HEIGHT as f32 - state.match_settings.ball_radius,
+            ),
+            Vector2::new(0.0, 1.0),
+        )];
+
+        step_world(&mut state, &[]);
+
+        assert!(state.world_data.balls.is_empty());
+        assert_eq!(
+            state
+                .world_data
+                .paddles
+                .iter()
+                .find(|p| p.id == 0)
+                .unwrap()
+                .respawn_countdown,
+            Some(BALL_RESPAWN_DELAY_SECONDS as u8)
+        );
+
+        for _ in 0..respawn_delay_ticks - 2 {
+            step_world(&mut state, &[]);
+            assert!(state.world_data.balls.is_empty());
+        }
+
+        step_world(&mut state, &[]);
+
+        let paddle = state.world_data.paddles.iter().find(|p| p.id == 0).unwrap();
+        assert_eq!(paddle.respawn_countdown, None);
+
+        assert_eq!(state.world_data.balls.len(), 1);
+        let ball = &state.world_data.balls[0];
+        assert_eq!(ball.owner, 0);
+        assert!(!ball.is_free);
+        assert_eq!(
+            ball.position,
+            Vector2::new(
+                paddle.position.x,
+                paddle.position.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius
+            )
+        );
+    }
+
+    #[test]
+    fn clearing_two_levels_loads_each_next_layout() {
+        let mut state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        let starting_balls = state.world_data.balls.clone();
+
+        for (next_level, block_count) in [(BlockLayout::Pyramid, 149), (BlockLayout::Fortress, 113)]
+        {
+            state.world_data.blocks.clear();
+            state.world_data.scores = Some([2, 3]);
+            step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+            assert!(state.world_data.balls.iter().any(|b| b.is_free));
+
+            load_level(&mut state, next_level);
+
+            assert_eq!(state.world_data.layout_name, next_level.name());
+            assert_eq!(block_ids(&state), (0..block_count).collect::<Vec<u32>>());
+            assert!(state.world_data.regenerating_blocks.is_empty());
+            assert_eq!(state.world_data.balls, starting_balls);
+            assert_eq!(state.world_data.scores, Some([2, 3]));
+        }
+    }
+
+    #[test]
+    fn training_ball_crossing_goal_line_does_not_score() {
+        let match_settings = MatchSettings {
+            training_ball: true,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let ball_radius = state.match_settings.ball_radius;
+
+        for (position_y, direction_y) in [(WORLD_HEIGHT as f32 - ball_radius, 1.0), (0.0, -1.0)] {
+            let training_ball_index = state
+                .world_data
+                .balls
+                .iter()
+                .position(|b| !b.scoring)
+                .unwrap();
+            let training_ball = &mut state.world_data.balls[training_ball_index];
+
+            training_ball.position = Vector2::new(200.0, position_y);
+            training_ball.velocity = Vector2::new(0.0, direction_y);
+
+            step_world(&mut state, &[]);
+
+            assert_eq!(state.world_data.scores, Some([0, 0]));
+            assert!(state.respawn_timers.is_empty());
+            assert_eq!(state.world_data.balls.len(), 3);
+
+            let training_ball = &state.world_data.balls[training_ball_index];
+            assert!(!training_ball.scoring);
+            let center = Vector2::new(WORLD_WIDTH as f32 / 2.0, WORLD_HEIGHT as f32 / 2.0);
+            assert!((training_ball.position - center).magnitude() < 10.0);
+        }
+    }
+
+    #[test]
+    fn rally_cap_resets_balls_to_serve_after_configured_ticks() {
+        let match_settings = MatchSettings {
+            max_rally_seconds: Some(2),
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let max_rally_ticks = (2.0 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        state.world_data.balls = vec![create_owned_ball(
+            0,
+            0,
+            Vector2::new(960.0, 540.0),
+            Vector2::new(1.0, 0.0),
+        )];
+
+        for _ in 0..max_rally_ticks - 1 {
+            step_world(&mut state, &[]);
+            assert!(state.world_data.balls[0].is_free);
+        }
+
+        assert_eq!(state.world_data.rally_countdown, Some(1));
+
+        step_world(&mut state, &[]);
+
+        let ball = &state.world_data.balls[0];
+        let paddle = paddle_position(&state, 0);
+
+        assert!(!ball.is_free);
+        assert_eq!(ball.position.x, paddle.x);
+        assert_eq!(state.world_data.rally_countdown, None);
+    }
+
+    #[test]
+    fn accelerated_paddle_reaches_max_speed_and_then_decelerates() {
+        let paddle_speed = MatchSettings::default().paddle_speed;
+        let match_settings = MatchSettings {
+            paddle_acceleration: Some(paddle_speed * 15.0),
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let speed_step = paddle_speed * 15.0 * GAME_LOOP_TIMESTEP_SECONDS;
+
+        for tick in 1..=3 {
+            step_world(&mut state, &[input(0, PlayerAction::MoveRight)]);
+
+            let speed = state.paddle_speeds[0];
+            assert!((speed - tick as f32 * speed_step).abs() < 1e-3, "{}", speed);
+            assert!(speed < paddle_speed);
+        }
+
+        for _ in 0..2 {
+            step_world(&mut state, &[input(0, PlayerAction::MoveRight)]);
+        }
+
+        assert_eq!(state.paddle_speeds[0], paddle_speed);
+
+        step_world(&mut state, &[input(0, PlayerAction::MoveRight)]);
+        assert_eq!(state.paddle_speeds[0], paddle_speed);
+
+        let mut previous_speed = state.paddle_speeds[0];
+        let mut previous_x = paddle_position(&state, 0).x;
+
+        for _ in 0..3 {
+            step_world(&mut state, &[]);
+
+            let speed = state.paddle_speeds[0];
+            let x = paddle_position(&state, 0).x;
+
+            assert!(
+                (previous_speed - speed - speed_step).abs() < 1e-3,
+                "{}",
+                speed
+            );
+            assert!(x > previous_x);
+
+            previous_speed = speed;
+            previous_x = x;
+        }
+
+        for _ in 0..2 {
+            step_world(&mut state, &[]);
+        }
+
+        assert_eq!(state.paddle_speeds[0], 0.0);
+        assert_eq!(state.paddle_speeds[1], 0.0);
+    }
+
+    #[test]
+    fn side_hit_region_separates_top_face_side_and_corner() {
+        let object_position = Vector2::new(500.0, 500.0);
+        let is_side_hit = |ball_position: Vector2<f32>| {
+            let ball = create_free_ball(0, ball_position, Vector2::new(0.0, 1.0));
+            is_ball_hit_side_of_object(&ball, 10.0, 0.0, object_position, 200, 20)
+        };
+
+        assert!(!is_side_hit(Vector2::new(500.0, 482.0)));
+        assert!(!is_side_hit(Vector2::new(595.0, 482.0)));
+        assert!(is_side_hit(Vector2::new(608.0, 500.0)));
+        assert!(is_side_hit(Vector2::new(392.0, 505.0)));
+        assert!(!is_side_hit(Vector2::new(608.0, 482.0)));
+        assert!(is_side_hit(Vector2::new(609.0, 484.0)));
+        assert!(!is_side_hit(Vector2::new(606.0, 481.0)));
+    }
+
+    #[test]
+    fn paddle_side_hit_reflects_or_passes_through() {
+        for (paddle_side_hit, expected_velocity_x) in [
+            (PaddleSideHit::Reflect, -1.0),
+            (PaddleSideHit::PassThrough, 1.0),
+        ] {
+            let match_settings = MatchSettings {
+                paddle_side_hit,
+                ..MatchSettings::default()
+            };
+            let mut state = create_empty_world(GameMode::Versus, match_settings);
+            let paddle = paddle_position(&state, 0);
+            let ball_radius = state.match_settings.ball_radius;
+
+            state.world_data.balls = vec![create_free_ball(
+                0,
+                Vector2::new(
+                    paddle.x - PADDLE_WIDTH as f32 / 2.0 - ball_radius + 2.0,
+                    paddle.y,
+                ),
+                Vector2::new(1.0, 0.0),
+            )];
+
+            step_world(&mut state, &[]);
+
+            let ball = &state.world_data.balls[0];
+            assert_eq!(ball.velocity, Vector2::new(expected_velocity_x, 0.0));
+        }
+    }
+
+    fn step_horizontal_ball_distance(state: &mut GameState, position_y: f32) -> f32 {
+        state.world_data.balls = vec![create_owned_ball(
+            0,
+            0,
+            Vector2::new(960.0, position_y),
+            Vector2::new(1.0, 0.0),
+        )];
+
+        step_world(state, &[]);
+
+        state.world_data.balls[0].position.x - 960.0
+    }
+
+    #[test]
+    fn mercy_slows_balls_in_trailing_half_only_past_the_gap() {
+        let match_settings = MatchSettings {
+            mercy_score_gap: Some(3),
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let full_distance = BALL_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
+
+        state.world_data.scores = Some([4, 1]);
+        let distance = step_horizontal_ball_distance(&mut state, 300.0);
+
+        assert_eq!(state.world_data.mercy_player, None);
+        assert!((distance - full_distance).abs() < 1e-3);
+
+        state.world_data.scores = Some([5, 1]);
+        let distance = step_horizontal_ball_distance(&mut state, 300.0);
+
+        assert_eq!(state.world_data.mercy_player, Some(1));
+        assert!((distance - full_distance * MERCY_BALL_SPEED_FACTOR).abs() < 1e-3);
+
+        let distance = step_horizontal_ball_distance(&mut state, 800.0);
+
+        assert!((distance - full_distance).abs() < 1e-3);
+
+        state.world_data.scores = Some([5, 2]);
+        let distance = step_horizontal_ball_distance(&mut state, 300.0);
+
+        assert_eq!(state.world_data.mercy_player, None);
+        assert!((distance - full_distance).abs() < 1e-3);
+    }
+
+    #[test]
+    fn mercy_helps_either_trailing_player() {
+        let match_settings = MatchSettings {
+            mercy_score_gap: Some(0),
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        state.world_data.scores = Some([0, 1]);
+        step_world(&mut state, &[]);
+        assert_eq!(state.world_data.mercy_player, Some(0));
+
+        state.world_data.scores = Some([1, 1]);
+        step_world(&mut state, &[]);
+        assert_eq!(state.world_data.mercy_player, None);
+    }
+
+    #[test]
+    fn split_paddle_segments_leave_a_centered_gap() {
+        let paddle = Paddle {
+            id: 0,
+            position: Vector2::new(960.0, 1060.0),
+            respawn_countdown: None,
+        };
+
+        let segments = paddle_segments(&paddle, Some(100));
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].position, Vector2::new(860.0, 1060.0));
+        assert_eq!(segments[1].position, Vector2::new(1060.0, 1060.0));
+        assert!(segments.iter().all(|s| s.width == PADDLE_WIDTH / 2));
+        assert_eq!(paddle_half_extent(Some(100)), 150.0);
+    }
+
+    #[test]
+    fn ball_passes_through_split_gap_and_bounces_off_segments() {
+        let match_settings = MatchSettings {
+            split_paddle_gap: Some(100),
+            ..MatchSettings::default()
+        };
+
+        for (x_offset, is_expected_to_bounce) in [
+            (-100.0, true),
+            (-20.0, false),
+            (0.0, false),
+            (20.0, false),
+            (100.0, true),
+        ] {
+            let mut state = create_empty_world(GameMode::Versus, match_settings.clone());
+            let paddle = paddle_position(&state, 0);
+
+            state.world_data.balls = vec![create_free_ball(
+                0,
+                Vector2::new(
+                    paddle.x + x_offset,
+                    paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 2.0,
+                ),
+                Vector2::new(0.0, 1.0),
+            )];
+
+            step_world(&mut state, &[]);
+
+            let velocity = state.world_data.balls[0].velocity;
+            assert_eq!(velocity.y < 0.0, is_expected_to_bounce, "{}", x_offset);
+        }
+    }
+
+    #[test]
+    fn paddle_stops_at_a_block_in_its_path() {
+        for paddle_block_collision in [true, false] {
+            let match_settings = MatchSettings {
+                paddle_block_collision,
+                ..MatchSettings::default()
+            };
+            let mut state = create_empty_world(GameMode::Versus, match_settings);
+            let paddle = paddle_position(&state, 0);
+            let touching_x = paddle.x + 3.0;
+
+            state.world_data.blocks = vec![create_block(
+                0,
+                Vector2::new(
+                    touching_x + (PADDLE_WIDTH + BLOCK_SIZE) as f32 / 2.0,
+                    paddle.y,
+                ),
+            )];
+
+            for _ in 0..3 {
+                step_world(&mut state, &[input(0, PlayerAction::MoveRight)]);
+            }
+
+            let stopped_x = paddle_position(&state, 0).x;
+
+            if paddle_block_collision {
+                assert_eq!(stopped_x, touching_x);
+            } else {
+                assert!(stopped_x > touching_x);
+            }
+
+            step_world(&mut state, &[input(0, PlayerAction::MoveLeft)]);
+            assert!(paddle_position(&state, 0).x < stopped_x);
+        }
+    }
+
+    fn launched_velocity(
+        launch_direction: LaunchDirection,
+        position_y: f32,
+        seed: u64,
+    ) -> Vector2<f32> {
+        let match_settings = MatchSettings {
+            launch_direction,
+            ..MatchSettings::default()
+        };
+        let mut random = SeededRandom::new(seed);
+        let mut ball = create_owned_ball(
+            0,
+            0,
+            Vector2::new(960.0, position_y),
+            Vector2::new(0.0, 1.0),
+        );
+        ball.is_free = false;
+
+        launch_ball(&mut ball, &match_settings, &mut random);
+
+        assert!(ball.is_free);
+        ball.velocity
+    }
+
+    #[test]
+    fn straight_launch_goes_vertically_away_from_the_paddle() {
+        for seed in 0..20 {
+            assert_eq!(
+                launched_velocity(LaunchDirection::Straight, 1040.0, seed),
+                Vector2::new(0.0, -1.0)
+            );
+            assert_eq!(
+                launched_velocity(LaunchDirection::Straight, 40.0, seed),
+                Vector2::new(0.0, 1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn cone_launch_spreads_within_the_cone() {
+        let angles: Vec<f32> = (0..200)
+            .map(|seed| {
+                let velocity = launched_velocity(LaunchDirection::Cone, 1040.0, seed);
+
+                assert!(velocity.y < 0.0);
+                assert!((velocity.magnitude() - 1.0).abs() < 1e-4);
+
+                velocity.x.atan2(-velocity.y).to_degrees()
+            })
+            .collect();
+
+        assert!(angles
+            .iter()
+            .all(|angle| angle.abs() <= LAUNCH_CONE_HALF_ANGLE_DEGREES + 1e-3));
+        assert!(angles
+            .iter()
+            .any(|angle| *angle < -LAUNCH_CONE_HALF_ANGLE_DEGREES / 2.0));
+        assert!(angles
+            .iter()
+            .any(|angle| *angle > LAUNCH_CONE_HALF_ANGLE_DEGREES / 2.0));
+
+        let mean_angle = angles.iter().sum::<f32>() / angles.len() as f32;
+        assert!(mean_angle.abs() < 5.0, "{}", mean_angle);
+    }
+
+    #[test]
+    fn toward_conceder_relaunches_training_ball_at_the_conceding_player() {
+        let match_settings = MatchSettings {
+            training_ball: true,
+            launch_direction: LaunchDirection::TowardConceder,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let ball_radius = state.match_settings.ball_radius;
+
+        for (position_y, direction_y, expected_direction_y) in [
+            (0.0, -1.0, -1.0),
+            (WORLD_HEIGHT as f32 - ball_radius, 1.0, 1.0),
+        ] {
+            let training_ball = state
+                .world_data
+                .balls
+                .iter_mut()
+                .find(|b| !b.scoring)
+                .unwrap();
+
+            training_ball.position = Vector2::new(200.0, position_y);
+            training_ball.velocity = Vector2::new(0.0, direction_y);
+
+            step_world(&mut state, &[]);
+
+            let training_ball = state.world_data.balls.iter().find(|b| !b.scoring).unwrap();
+            assert_eq!(training_ball.velocity.y.signum(), expected_direction_y);
+        }
+    }
+
+    #[test]
+    fn paddle_delta_per_tick_stays_under_the_cap() {
+        for paddle_acceleration in [None, Some(100_000.0)] {
+            let match_settings = MatchSettings {
+                paddle_acceleration,
+                ..MatchSettings::default()
+            };
+            let max_paddle_delta = max_paddle_delta_per_tick(&match_settings);
+            let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+            let burst: Vec<PlayerInput> = (0..10)
+                .map(|_| input(0, PlayerAction::MoveRight))
+                .chain((0..3).map(|_| input(1, PlayerAction::MoveLeft)))
+                .collect();
+
+            for _ in 0..20 {
+                let previous_paddles = state.world_data.paddles.clone();
+
+                step_world(&mut state, &burst);
+
+                for (paddle, previous_paddle) in
+                    state.world_data.paddles.iter().zip(previous_paddles.iter())
+                {
+                    let delta = (paddle.position.x - previous_paddle.position.x).abs();
+                    assert!(delta <= max_paddle_delta + 1e-3, "{}", delta);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn opposite_moves_in_one_tick_cancel_out() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let paddle = paddle_position(&state, 0);
+
+        step_world(
+            &mut state,
+            &[
+                input(0, PlayerAction::MoveRight),
+                input(0, PlayerAction::MoveRight),
+                input(0, PlayerAction::MoveLeft),
+                input(0, PlayerAction::MoveLeft),
+            ],
+        );
+
+        assert_eq!(paddle_position(&state, 0), paddle);
+    }
+
+    fn create_regenerating_world(block: &Block) -> GameState {
+        let match_settings = MatchSettings {
+            block_regeneration_seconds: Some(1),
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Practice, match_settings);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            ball_below_block(block, 2.0, 0.0),
+            Vector2::new(0.0, -1.0),
+        )];
+        state.world_data.blocks = vec![block.clone()];
+        state.level_blocks = vec![block.clone()];
+
+        state
+    }
+
+    #[test]
+    fn destroyed_block_regenerates_after_the_delay() {
+        let block = Block {
+            hits_life: 2,
+            ..create_block(4, Vector2::new(500.0, 300.0))
+        };
+        let mut state = create_regenerating_world(&block);
+        let regeneration_ticks = (1.0 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        state.world_data.blocks[0].hits_life = 1;
+        step_world(&mut state, &[]);
+
+        assert!(state.world_data.blocks.is_empty());
+
+        for _ in 1..regeneration_ticks {
+            let previous_progress = state.world_data.regenerating_blocks[0].progress;
+
+            assert!(state.world_data.blocks.is_empty());
+
+            step_world(&mut state, &[]);
+
+            if let Some(regenerating_block) = state.world_data.regenerating_blocks.first() {
+                assert!(regenerating_block.progress > previous_progress);
+            }
+        }
+
+        assert_eq!(state.world_data.blocks, vec![block]);
+        assert!(state.world_data.regenerating_blocks.is_empty());
+    }
+
+    #[test]
+    fn block_waits_for_a_ball_to_leave_before_regenerating() {
+        let block = create_block(4, Vector2::new(500.0, 300.0));
+        let mut state = create_regenerating_world(&block);
+        let regeneration_ticks = (1.0 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        step_world(&mut state, &[]);
+        assert!(state.world_data.blocks.is_empty());
+
+        for _ in 2..regeneration_ticks {
+            step_world(&mut state, &[]);
+        }
+
+        state.world_data.balls[0].position = block.position;
+
+        for _ in 0..5 {
+            step_world(&mut state, &[]);
+            state.world_data.balls[0].position = block.position;
+        }
+
+        assert!(state.world_data.blocks.is_empty());
+        assert_eq!(state.world_data.regenerating_blocks[0].progress, 1.0);
+
+        state.world_data.balls[0].position = Vector2::new(500.0, 800.0);
+        step_world(&mut state, &[]);
+
+        assert_eq!(state.world_data.blocks, vec![block]);
+    }
+
+    #[test]
+    fn launch_is_ignored_during_match_countdown() {
+        let mut state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        state.world_data.match_countdown = Some(3);
+
+        let serve_timer_ticks = (SERVE_TIMER_SECONDS as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        for _ in 0..serve_timer_ticks + 1 {
+            step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        }
+
+        assert!(state.world_data.balls.iter().all(|ball| !ball.is_free));
+
+        state.world_data.match_countdown = None;
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+
+        assert!(state
+            .world_data
+            .balls
+            .iter()
+            .any(|ball| ball.owner == 0 && ball.is_free));
+        assert!(state
+            .world_data
+            .balls
+            .iter()
+            .all(|ball| ball.owner == 0 || !ball.is_free));
+    }
+
+    fn create_breakthrough_row_world() -> GameState {
+        let match_settings = MatchSettings {
+            breakthrough_seconds: Some(10),
+            ..MatchSettings::default()
+        };
+        let grid = create_world(BlockLayout::Grid, GameMode::Versus, match_settings.clone());
+        let row_y = grid.world_data.blocks[0].position.y;
+
+        let ball_radius = match_settings.ball_radius;
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        state.world_data.blocks = grid
+            .world_data
+            .blocks
+            .iter()
+            .filter(|b| b.position.y == row_y)
+            .cloned()
+            .collect();
+        state.world_data.balls = vec![create_owned_ball(
+            0,
+            0,
+            Vector2::new(ball_radius + 1.0, row_y),
+            Vector2::new(1.0, 0.0),
+        )];
+        state
+    }
+
+    #[test]
+    fn breakthrough_ball_clears_a_full_row_in_one_pass() {
+        let mut state = create_breakthrough_row_world();
+        assert!(state.world_data.blocks.len() > 1);
+        assert_eq!(grant_breakthrough(&mut state, 0), 1);
+
+        while state.world_data.balls[0].velocity.x > 0.0 && !state.world_data.blocks.is_empty() {
+            step_world(&mut state, &[]);
+        }
+
+        assert!(state.world_data.blocks.is_empty());
+        assert!(state.world_data.balls[0].velocity.x > 0.0);
+        assert!(state.world_data.balls[0].breakthrough_ticks > 0);
+    }
+
+    #[test]
+    fn ball_without_breakthrough_reflects_off_the_first_block_in_the_row() {
+        let mut state = create_breakthrough_row_world();
+        let row_block_count = state.world_data.blocks.len();
+
+        while state.world_data.balls[0].velocity.x > 0.0 {
+            step_world(&mut state, &[]);
+        }
+
+        assert_eq!(state.world_data.blocks.len(), row_block_count - 1);
+    }
+
+    #[test]
+    fn breakthrough_is_only_granted_by_the_explicit_trigger() {
+        let match_settings = MatchSettings {
+            breakthrough_seconds: Some(10),
+            ..MatchSettings::default()
+        };
+        let mut state = create_world(BlockLayout::Grid, GameMode::Versus, match_settings);
+
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+
+        assert!(state.world_data.balls.iter().any(|ball| ball.is_free));
+        assert!(state
+            .world_data
+            .balls
+            .iter()
+            .all(|ball| ball.breakthrough_ticks == 0));
+
+        assert_eq!(grant_breakthrough(&mut state, 1), 0);
+        assert_eq!(grant_breakthrough(&mut state, 0), 1);
+
+        let mut disabled_state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        step_world(&mut disabled_state, &[input(0, PlayerAction::Launch)]);
+
+        assert_eq!(grant_breakthrough(&mut disabled_state, 0), 0);
+    }
+
+    fn replay_scripted_match(ticks: usize) -> Vec<WorldData> {
+        let match_settings = MatchSettings {
+            block_regeneration_seconds: Some(3),
+            bounce_jitter: 0.2,
+            random_seed: 42,
+            ..MatchSettings::default()
+        };
+        let mut state = create_world(BlockLayout::Grid, GameMode::Versus, match_settings);
+
+        (0..ticks)
+            .map(|tick| {
+                let inputs = match tick % 7 {
+                    0 => vec![
+                        input(0, PlayerAction::Launch),
+                        input(1, PlayerAction::MoveLeft),
+                    ],
+                    1..=3 => vec![
+                        input(0, PlayerAction::MoveRight),
+                        input(1, PlayerAction::Launch),
+                    ],
+                    _ => vec![
+                        input(0, PlayerAction::MoveLeft),
+                        input(1, PlayerAction::MoveRight),
+                    ],
+                };
+
+                step_world(&mut state, &inputs);
+                state.world_data.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replaying_the_same_inputs_gives_the_same_world() {
+        let first_run = replay_scripted_match(1800);
+        let second_run = replay_scripted_match(1800);
+
+        assert!(first_run[0].blocks.len() > first_run[1799].blocks.len());
+
+        for (first, second) in first_run.iter().zip(second_run.iter()) {
+            assert_eq!(first, second, "Runs diverged at tick {}", first.tick);
+        }
+    }
+
+    fn reversal_distance_from_wall(match_settings: MatchSettings, direction_x: f32) -> f32 {
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(963.0, 300.0),
+            Vector2::new(direction_x, 0.0),
+        )];
+
+        for _ in 0..1000 {
+            step_world(&mut state, &[]);
+
+            let ball = &state.world_data.balls[0];
+
+            if ball.velocity.x.signum() != direction_x.signum() {
+                let step = ball.velocity.x * BALL_SPEED as f32 * GAME_LOOP_TIMESTEP_SECONDS;
+                let reversal_x = ball.position.x - step;
+
+                return if direction_x < 0.0 {
+                    reversal_x
+                } else {
+                    WORLD_WIDTH as f32 - reversal_x
+                };
+            }
+        }
+
+        panic!("Ball never reached the wall");
+    }
+
+    #[test]
+    fn ball_reverses_at_same_distance_from_both_walls() {
+        let left_distance = reversal_distance_from_wall(MatchSettings::default(), -1.0);
+        let right_distance = reversal_distance_from_wall(MatchSettings::default(), 1.0);
+
+        let ball_radius = MatchSettings::default().ball_radius;
+
+        assert!(
+            (left_distance - ball_radius).abs() < 1e-3,
+            "{}",
+            left_distance
+        );
+        assert!(
+            (right_distance - ball_radius).abs() < 1e-3,
+            "{}",
+            right_distance
+        );
+    }
+
+    #[test]
+    fn large_ball_reverses_at_same_distance_from_both_walls() {
+        let match_settings = MatchSettings {
+            ball_radius: MatchSettings::default().ball_radius * 2.0,
+            ..MatchSettings::default()
+        };
+
+        let left_distance = reversal_distance_from_wall(match_settings.clone(), -1.0);
+        let right_distance = reversal_distance_from_wall(match_settings.clone(), 1.0);
+
+        assert!(
+            (left_distance - match_settings.ball_radius).abs() < 1e-3,
+            "{}",
+            left_distance
+        );
+        assert!(
+            (right_distance - match_settings.ball_radius).abs() < 1e-3,
+            "{}",
+            right_distance
+        );
+    }
+
+    #[test]
+    fn pausing_preserves_positions_and_timers() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+
+        state.world_data.balls.push(create_free_ball(
+            2,
+            Vector2::new(700.0, 500.0),
+            Vector2::new(0.3, 1.0),
+        ));
+
+        let mut lost_ball = create_free_ball(3, Vector2::new(300.0, 5.0), Vector2::new(0.0, -1.0));
+        lost_ball.owner = 1;
+        state.world_data.balls.push(lost_ball);
+
+        for _ in 0..10 {
+            step_world(&mut state, &[]);
+        }
+
+        assert!(!state.respawn_timers.is_empty());
+
+        step_world(&mut state, &[input(0, PlayerAction::TogglePause)]);
+        assert!(state.world_data.is_paused);
+
+        let paused_world_data = state.world_data.clone();
+        let paused_respawn_timers = state.respawn_timers.clone();
+
+        for _ in 0..500 {
+            step_world(
+                &mut state,
+                &[
+                    input(0, PlayerAction::MoveLeft),
+                    input(1, PlayerAction::MoveRight),
+                    input(0, PlayerAction::Launch),
+                ],
+            );
+        }
+
+        assert_eq!(state.world_data, paused_world_data);
+        assert_eq!(state.respawn_timers, paused_respawn_timers);
+
+        step_world(&mut state, &[input(1, PlayerAction::TogglePause)]);
+        assert!(!state.world_data.is_paused);
+        assert_eq!(state.world_data.tick, paused_world_data.tick + 1);
+        assert_eq!(state.respawn_timers[0].1, paused_respawn_timers[0].1 - 1);
+    }
+
+    #[test]
+    fn gravity_increases_downward_velocity_of_launched_ball() {
+        let match_settings = MatchSettings {
+            gravity: 600.0,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        step_world(&mut state, &[input(1, PlayerAction::Launch)]);
+
+        let launched_ball_id = state
+            .world_data
+            .balls
+            .iter()
+            .find(|b| b.owner == 1)
+            .unwrap()
+            .id;
+
+        let mut previous_velocity_y = 0.0;
+
+        for _ in 0..10 {
+            let ball = state
+                .world_data
+                .balls
+                .iter()
+                .find(|b| b.id == launched_ball_id)
+                .unwrap();
+
+            assert!(ball.is_free);
+            assert!(ball.velocity.y > previous_velocity_y, "{:?}", ball.velocity);
+            previous_velocity_y = ball.velocity.y;
+
+            step_world(&mut state, &[]);
+        }
+    }
+
+    #[test]
+    fn launched_ball_keeps_constant_velocity_without_gravity() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+
+        step_world(&mut state, &[input(1, PlayerAction::Launch)]);
+
+        for _ in 0..10 {
+            step_world(&mut state, &[]);
+
+            let ball = state
+                .world_data
+                .balls
+                .iter()
+                .find(|b| b.owner == 1)
+                .unwrap();
+            assert_eq!(ball.velocity, Vector2::new(0.0, 1.0));
+        }
+    }
+
+    fn create_block(id: u32, position: Vector2<f32>) -> Block {
+        Block {
+            id,
+            position,
+            hits_life: 1,
+        }
+    }
+
+    #[test]
+    fn collision_scales_with_ball_radius() {
+        let block = create_block(0, Vector2::new(500.0, 500.0));
+        let gap_to_block = BLOCK_SIZE as f32 / 2.0 + 15.0;
+
+        for offset in [
+            Vector2::new(0.0, -gap_to_block),
+            Vector2::new(0.0, gap_to_block),
+            Vector2::new(-gap_to_block, 0.0),
+            Vector2::new(gap_to_block, 0.0),
+        ] {
+            let ball = create_free_ball(0, block.position + offset, Vector2::new(0.0, 1.0));
+
+            for (ball_radius, is_expected_to_collide) in [(10.0, false), (20.0, true)] {
+                assert_eq!(
+                    is_ball_collided_with_object(
+                        &ball,
+                        ball_radius,
+                        0.0,
+                        block.position,
+                        BLOCK_SIZE,
+                        BLOCK_SIZE
+                    ),
+                    is_expected_to_collide,
+                    "radius {} at offset {:?}",
+                    ball_radius,
+                    offset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn doubled_ball_radius_hits_block_from_further_away() {
+        for (ball_radius, expected_blocks_left) in [(10.0, 1), (20.0, 0)] {
+            let match_settings = MatchSettings {
+                ball_radius,
+                ..MatchSettings::default()
+            };
+            let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+            state.world_data.blocks = vec![create_block(0, Vector2::new(500.0, 500.0))];
+            state.world_data.balls = vec![create_free_ball(
+                7,
+                Vector2::new(500.0, 500.0 + BLOCK_SIZE as f32 / 2.0 + 15.0),
+                Vector2::new(1.0, 0.0),
+            )];
+
+            step_world(&mut state, &[]);
+
+            assert_eq!(
+                state.world_data.blocks.len(),
+                expected_blocks_left,
+                "radius {}",
+                ball_radius
+            );
+        }
+    }
+
+    fn create_owned_ball(
+        id: u8,
+        owner: u8,
+        position: Vector2<f32>,
+        velocity: Vector2<f32>,
+    ) -> Ball {
+        Ball {
+            owner,
+            ..create_free_ball(id, position, velocity)
+        }
+    }
+
+    fn ball_below_block(block: &Block, overlap: f32, x_offset: f32) -> Vector2<f32> {
+        Vector2::new(
+            block.position.x + x_offset,
+            block.position.y + BLOCK_SIZE as f32 / 2.0 + MatchSettings::default().ball_radius
+                - overlap,
+        )
+    }
+
+    #[test]
+    fn destroyed_block_is_credited_to_ball_owner() {
+        for owner in [0, 1] {
+            let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+            let block = create_block(0, Vector2::new(500.0, 500.0));
+
+            state.world_data.balls = vec![create_owned_ball(
+                5,
+                owner,
+                ball_below_block(&block, 2.0, 0.0),
+                Vector2::new(0.0, -1.0),
+            )];
+            state.world_data.blocks = vec![block];
+
+            step_world(&mut state, &[]);
+
+            let mut expected_scores = [0, 0];
+            expected_scores[owner as usize] = 1;
+
+            assert!(state.world_data.blocks.is_empty());
+            assert_eq!(state.world_data.scores, Some(expected_scores));
+        }
+    }
+
+    #[test]
+    fn block_between_two_owners_is_credited_to_the_closer_ball() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let block = create_block(0, Vector2::new(500.0, 500.0));
+
+        state.world_data.balls = vec![
+            create_owned_ball(
+                5,
+                0,
+                ball_below_block(&block, 2.0, -10.0),
+                Vector2::new(0.0, -1.0),
+            ),
+            create_owned_ball(
+                6,
+                1,
+                ball_below_block(&block, 4.0, 10.0),
+                Vector2::new(0.0, -1.0),
+            ),
+        ];
+        state.world_data.blocks = vec![block];
+
+        step_world(&mut state, &[]);
+
+        assert!(state.world_data.blocks.is_empty());
+        assert_eq!(state.world_data.scores, Some([0, 1]));
+
+        let far_ball = state.world_data.balls.iter().find(|b| b.id == 5).unwrap();
+        let close_ball = state.world_data.balls.iter().find(|b| b.id == 6).unwrap();
+        assert!(far_ball.velocity.y < 0.0);
+        assert!(close_ball.velocity.y > 0.0);
+    }
+
+    #[test]
+    fn equally_close_balls_credit_the_block_to_the_lower_id() {
+        for is_lower_id_first in [true, false] {
+            let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+            let block = create_block(0, Vector2::new(500.0, 500.0));
+
+            let lower_id_ball = create_owned_ball(
+                3,
+                1,
+                ball_below_block(&block, 2.0, 10.0),
+                Vector2::new(0.0, -1.0),
+            );
+            let higher_id_ball = create_owned_ball(
+                7,
+                0,
+                ball_below_block(&block, 2.0, -10.0),
+                Vector2::new(0.0, -1.0),
+            );
+
+            state.world_data.balls = if is_lower_id_first {
+                vec![lower_id_ball, higher_id_ball]
+            } else {
+                vec![higher_id_ball, lower_id_ball]
+            };
+            state.world_data.blocks = vec![block];
+
+            step_world(&mut state, &[]);
+
+            assert!(state.world_data.blocks.is_empty());
+            assert_eq!(state.world_data.scores, Some([0, 1]));
+
+            let credited_ball = state.world_data.balls.iter().find(|b| b.id == 3).unwrap();
+            let other_ball = state.world_data.balls.iter().find(|b| b.id == 7).unwrap();
+            assert!(credited_ball.velocity.y > 0.0);
+            assert!(other_ball.velocity.y < 0.0);
+        }
+    }
+
+    #[test]
+    fn block_that_survives_a_hit_awards_no_points() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let block = Block {
+            hits_life: 2,
+            ..create_block(0, Vector2::new(500.0, 500.0))
+        };
+
+        state.world_data.balls = vec![create_owned_ball(
+            5,
+            0,
+            ball_below_block(&block, 2.0, 0.0),
+            Vector2::new(0.0, -1.0),
+        )];
+        state.world_data.blocks = vec![block];
+
+        step_world(&mut state, &[]);
+
+        assert_eq!(state.world_data.blocks[0].hits_life, 1);
+        assert_eq!(state.world_data.scores, Some([0, 0]));
+    }
+
+    #[test]
+    fn either_player_launches_the_shared_ball() {
+        for player_id in [0, 1] {
+            let mut state = create_empty_world(GameMode::SharedBall, MatchSettings::default());
+
+            assert_eq!(state.world_data.balls.len(), 1);
+            assert!(!state.world_data.balls[0].is_free);
+
+            step_world(&mut state, &[input(player_id, PlayerAction::Launch)]);
+
+            assert!(state.world_data.balls[0].is_free, "player {}", player_id);
+        }
+    }
+
+    #[test]
+    fn shared_ball_goal_is_credited_to_the_opposite_side() {
+        let ball_radius = MatchSettings::default().ball_radius;
+
+        for (position, velocity_y, conceding_player_id, expected_scores) in [
+            (WORLD_HEIGHT as f32 - ball_radius, 1.0, 0, [0, 1]),
+            (0.0, -1.0, 1, [1, 0]),
+        ] {
+            let mut state = create_empty_world(GameMode::SharedBall, MatchSettings::default());
+
+            state.world_data.balls = vec![create_free_ball(
+                0,
+                Vector2::new(100.0, position),
+                Vector2::new(0.0, velocity_y),
+            )];
+
+            step_world(&mut state, &[]);
+
+            assert!(state.world_data.balls.is_empty());
+            assert_eq!(state.world_data.scores, Some(expected_scores));
+            assert_eq!(state.respawn_timers.len(), 1);
+            assert_eq!(state.respawn_timers[0].0, conceding_player_id);
+        }
+    }
+
+    #[test]
+    fn both_goal_lines_account_for_the_ball_radius() {
+        let state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let ball_radius = state.match_settings.ball_radius;
+        let velocity = Vector2::new(0.0, 1.0);
+
+        for (edge_distance, expected) in [(0.0, true), (1.0, false)] {
+            let bottom_ball = create_free_ball(
+                0,
+                Vector2::new(100.0, WORLD_HEIGHT as f32 - ball_radius - edge_distance),
+                velocity,
+            );
+            let top_ball = create_free_ball(
+                1,
+                Vector2::new(100.0, ball_radius + edge_distance),
+                velocity,
+            );
+
+            assert_eq!(
+                conceding_player_id(&bottom_ball, ball_radius, &state),
+                expected.then_some(0)
+            );
+            assert_eq!(
+                conceding_player_id(&top_ball, ball_radius, &state),
+                expected.then_some(1)
+            );
+        }
+    }
+
+    fn is_ball_touching_block_with_margin(overlap: f32, collision_margin: f32) -> bool {
+        let block = create_block(0, Vector2::new(500.0, 500.0));
+        let ball = create_free_ball(
+            0,
+            ball_below_block(&block, overlap, 0.0),
+            Vector2::new(0.0, -1.0),
+        );
+
+        is_ball_collided_with_object(
+            &ball,
+            MatchSettings::default().ball_radius,
+            collision_margin,
+            block.position,
+            BLOCK_SIZE,
+            BLOCK_SIZE,
+        )
+    }
+
+    #[test]
+    fn positive_collision_margin_registers_grazing_hit() {
+        assert!(!is_ball_touching_block_with_margin(-1.0, 0.0));
+        assert!(is_ball_touching_block_with_margin(-1.0, 2.0));
+    }
+
+    #[test]
+    fn negative_collision_margin_ignores_grazing_hit() {
+        assert!(is_ball_touching_block_with_margin(1.0, 0.0));
+        assert!(!is_ball_touching_block_with_margin(1.0, -2.0));
+    }
+
+    #[test]
+    fn collision_margin_decides_whether_grazing_ball_destroys_block() {
+        for (collision_margin, expected_blocks_left) in [(2.0, 0), (0.0, 1), (-2.0, 1)] {
+            let match_settings = MatchSettings {
+                collision_margin,
+                ..MatchSettings::default()
+            };
+            let mut state = create_empty_world(GameMode::Versus, match_settings);
+            let block = create_block(0, Vector2::new(500.0, 500.0));
+
+            state.world_data.balls = vec![create_free_ball(
+                5,
+                ball_below_block(&block, -1.0, 0.0),
+                Vector2::new(1.0, 0.0),
+            )];
+            state.world_data.blocks = vec![block];
+
+            step_world(&mut state, &[]);
+
+            assert_eq!(
+                state.world_data.blocks.len(),
+                expected_blocks_left,
+                "margin {}",
+                collision_margin
+            );
+        }
+    }
+
+    fn step_until_respawned(state: &mut GameState) {
+        let respawn_delay_ticks =
+            (BALL_RESPAWN_DELAY_SECONDS as f32 / GAME_LOOP_TIMESTEP_SECONDS) as usize;
+
+        for _ in 0..respawn_delay_ticks {
+            step_world(state, &[]);
+        }
+    }
+
+    #[test]
+    fn balls_respawn_at_offset_paddles_for_both_players() {
+        let match_settings = MatchSettings {
+            paddle_offsets: [80.0, 120.0],
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let ball_offset_from_paddle = PADDLE_HEIGHT as f32 / 2.0 + state.match_settings.ball_radius;
+
+        assert_eq!(paddle_position(&state, 0).y, WORLD_HEIGHT as f32 - 80.0);
+        assert_eq!(paddle_position(&state, 1).y, 120.0);
+
+        state.world_data.balls = vec![
+            create_owned_ball(
+                0,
+                0,
+                Vector2::new(
+                    100.0,
+                    WORLD_HEIGHT as f32 - state.match_settings.ball_radius,
+                ),
+                Vector2::new(0.0, 1.0),
+            ),
+            create_owned_ball(1, 1, Vector2::new(100.0, 0.0), Vector2::new(0.0, -1.0)),
+        ];
+
+        step_until_respawned(&mut state);
+
+        assert_eq!(state.world_data.balls.len(), 2);
+
+        for ball in &state.world_data.balls {
+            let paddle = paddle_position(&state, ball.owner);
+            let expected_y = if ball.owner == 0 {
+                paddle.y - ball_offset_from_paddle
+            } else {
+                paddle.y + ball_offset_from_paddle
+            };
+
+            assert!(!ball.is_free);
+            assert_eq!(ball.position, Vector2::new(paddle.x, expected_y));
+        }
+    }
+
+    #[test]
+    fn block_ids_stay_stable_when_blocks_are_destroyed() {
+        let mut state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        let original_blocks = state.world_data.blocks.clone();
+        let destroyed_ids = [150, 170];
+
+        state.world_data.balls = destroyed_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                let block = original_blocks.iter().find(|b| b.id == *id).unwrap();
+                create_free_ball(
+                    index as u8,
+                    ball_below_block(block, 2.0, 0.0),
+                    Vector2::new(0.0, -1.0),
+                )
+            })
+            .collect();
+
+        for _ in 0..10 {
+            step_world(&mut state, &[]);
+
+            for block in &state.world_data.blocks {
+                let original_block = original_blocks.iter().find(|b| b.id == block.id).unwrap();
+                assert_eq!(block, original_block);
+            }
+        }
+
+        let remaining_ids: Vec<u32> = state.world_data.blocks.iter().map(|b| b.id).collect();
+        let expected_ids: Vec<u32> = original_blocks
+            .iter()
+            .map(|b| b.id)
+            .filter(|id| !destroyed_ids.contains(id))
+            .collect();
+
+        assert_eq!(remaining_ids, expected_ids);
+    }
+
+    fn is_ball_of_player_free(state: &GameState, player_id: u8) -> bool {
+        state
+            .world_data
+            .balls
+            .iter()
+            .any(|b| b.owner == player_id && b.is_free)
+    }
+
+    #[test]
+    fn launch_is_ignored_until_both_players_are_ready() {
+        let match_settings = MatchSettings {
+            require_ready: true,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        assert_eq!(state.world_data.ready_players, Some([false, false]));
+
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        assert!(!is_ball_of_player_free(&state, 0));
+
+        step_world(&mut state, &[input(0, PlayerAction::Ready)]);
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        assert_eq!(state.world_data.ready_players, Some([true, false]));
+        assert!(!is_ball_of_player_free(&state, 0));
+        assert!(state.world_data.balls.iter().all(|b| b.attached_ticks == 0));
+
+        step_world(&mut state, &[input(1, PlayerAction::Ready)]);
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        assert_eq!(state.world_data.ready_players, Some([true, true]));
+        assert!(is_ball_of_player_free(&state, 0));
+        assert!(!is_ball_of_player_free(&state, 1));
+    }
+
+    fn vertical_paddle_bounce_velocity(bounce_jitter: f32, random_seed: u64) -> Vector2<f32> {
+        let match_settings = MatchSettings {
+            bounce_jitter,
+            random_seed,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let paddle = paddle_position(&state, 0);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(
+                paddle.x,
+                paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 2.0,
+            ),
+            Vector2::new(0.0, 1.0),
+        )];
+
+        step_world(&mut state, &[]);
+
+        let velocity = state.world_data.balls[0].velocity;
+        assert!(velocity.y < 0.0);
+
+        velocity
+    }
+
+    #[test]
+    fn vertical_ball_gets_x_velocity_only_with_jitter() {
+        for seed in 0..10 {
+            assert_eq!(vertical_paddle_bounce_velocity(0.0, seed).x, 0.0);
+        }
+
+        assert!((0..10).any(|seed| vertical_paddle_bounce_velocity(0.3, seed).x != 0.0));
+    }
+
+    #[test]
+    fn jitter_is_deterministic_per_seed_and_keeps_speed() {
+        for seed in 0..10 {
+            let velocity = vertical_paddle_bounce_velocity(0.3, seed);
+
+            assert_eq!(velocity, vertical_paddle_bounce_velocity(0.3, seed));
+            assert!((velocity.magnitude() - 1.0).abs() < 1e-4, "{:?}", velocity);
+            assert!(velocity.x.abs() <= 0.3 + 1e-4, "{:?}", velocity);
+        }
+    }
+
+    fn paddle_bounce_slope(deflection_curve: DeflectionCurve, offset_from_center: f32) -> f32 {
+        let match_settings = MatchSettings {
+            deflection_curve,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+        let paddle = paddle_position(&state, 0);
+
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(
+                paddle.x + offset_from_center,
+                paddle.y - PADDLE_HEIGHT as f32 / 2.0 - state.match_settings.ball_radius + 2.0,
+            ),
+            Vector2::new(0.0, 1.0),
+        )];
+
+        step_world(&mut state, &[]);
+
+        let velocity = state.world_data.balls[0].velocity;
+        assert!(velocity.y < 0.0);
+
+        velocity.x / -velocity.y
+    }
+
+    #[test]
+    fn deflection_curves_at_center_quarter_and_edge() {
+        let half_width = PADDLE_WIDTH as f32 / 2.0;
+        let past_edge = half_width * 1.05;
+
+        let cases = [
+            (DeflectionCurve::Linear, [0.0, 0.5, -0.5, 1.0, 1.05]),
+            (DeflectionCurve::Quadratic, [0.0, 0.25, -0.25, 1.0, 1.1025]),
+            (DeflectionCurve::Clamped, [0.0, 0.5, -0.5, 0.6, 0.6]),
+        ];
+
+        for (deflection_curve, expected_slopes) in cases {
+            let offsets = [
+                0.0,
+                half_width / 2.0,
+                -half_width / 2.0,
+                half_width,
+                past_edge,
+            ];
+
+            for (offset, expected_slope) in offsets.into_iter().zip(expected_slopes) {
+                let slope = paddle_bounce_slope(deflection_curve, offset);
+
+                assert!(
+                    (slope - expected_slope).abs() < 1e-4,
+                    "{} curve at offset {} gave {} instead of {}",
+                    deflection_curve.name(),
+                    offset,
+                    slope,
+                    expected_slope
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn deflection_curves_keep_the_sign_of_the_offset() {
+        for deflection_curve in DeflectionCurve::ALL {
+            for deflect_factor in [-1.2, -1.0, -0.5, 0.5, 1.0, 1.2] {
+                let deflected = apply_deflection_curve(deflection_curve, deflect_factor);
+
+                assert_eq!(deflected.signum(), f32::signum(deflect_factor));
+            }
+        }
+    }
+
+    #[test]
+    fn clamped_deflection_differs_from_linear_near_the_paddle_edge() {
+        let near_edge = PADDLE_WIDTH as f32 / 2.0 * 0.9;
+
+        let linear_slope = paddle_bounce_slope(DeflectionCurve::Linear, near_edge);
+        let clamped_slope = paddle_bounce_slope(DeflectionCurve::Clamped, near_edge);
+
+        assert!((clamped_slope - CLAMPED_DEFLECTION_LIMIT).abs() < 1e-4);
+        assert!(clamped_slope < linear_slope);
+    }
+
+    // Golden runs: the numbers below were recorded from the current implementation and pin its
+    // behaviour, so any change to world creation or stepping shows up here first.
+    fn run_golden_script(state: &mut GameState) {
+        step_world(
+            state,
+            &[
+                input(0, PlayerAction::Launch),
+                input(1, PlayerAction::Launch),
+            ],
+        );
+
+        for _ in 0..299 {
+            step_world(state, &[input(0, PlayerAction::MoveLeft)]);
+        }
+    }
+
+    fn block_ids(state: &GameState) -> Vec<u32> {
+        state.world_data.blocks.iter().map(|b| b.id).collect()
+    }
+
+    #[test]
+    fn golden_create_world_versus_grid() {
+        let state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+        let world_data = &state.world_data;
+
+        assert_eq!(world_data.tick, 0);
+        assert_eq!(world_data.layout_name, "grid");
+        assert_eq!(world_data.scores, Some([0, 0]));
+        assert_eq!(block_ids(&state), (0..185).collect::<Vec<u32>>());
+        assert_eq!(
+            world_data.blocks.first(),
+            Some(&create_block(0, Vector2::new(42.0, 440.0)))
+        );
+        assert_eq!(
+            world_data.blocks.last(),
+            Some(&create_block(184, Vector2::new(1878.0, 644.0)))
+        );
+
+        assert_eq!(
+            world_data.paddles,
+            vec![
+                Paddle {
+                    id: 1,
+                    position: Vector2::new(960.0, 20.0),
+                    respawn_countdown: None,
+                },
+                Paddle {
+                    id: 0,
+                    position: Vector2::new(960.0, 1060.0),
+                    respawn_countdown: None,
+                },
+            ]
+        );
+
+        let attached_ball = |id, owner, y| Ball {
+            id,
+            owner,
+            position: Vector2::new(960.0, y),
+            velocity: Vector2::new(0.0, 0.0),
+            is_free: false,
+            scoring: true,
+            attached_ticks: 0,
+            serve_countdown: None,
+            breakthrough_ticks: 0,
+            paddle_collision_cooldown_ticks: 0,
+        };
+
+        assert_eq!(
+            world_data.balls,
+            vec![attached_ball(0, 1, 40.0), attached_ball(1, 0, 1040.0)]
+        );
+    }
+
+    #[test]
+    fn golden_step_world_versus_grid() {
+        let mut state = create_world(
+            BlockLayout::Grid,
+            GameMode::Versus,
+            MatchSettings::default(),
+        );
+
+        run_golden_script(&mut state);
+
+        let world_data = &state.world_data;
+
+        assert_eq!(world_data.tick, 300);
+        assert_eq!(world_data.scores, Some([1, 3]));
+        assert_eq!(
+            block_ids(&state),
+            (0..185)
+                .filter(|id| ![18, 55, 166].contains(id))
+                .collect::<Vec<u32>>()
+        );
+        assert_eq!(paddle_position(&state, 0), Vector2::new(100.0, 1060.0));
+        assert_eq!(paddle_position(&state, 1), Vector2::new(960.0, 20.0));
+        assert_eq!(
+            world_data.balls,
+            vec![Ball {
+                owner: 1,
+                ..create_free_ball(0, Vector2::new(960.0, 125.0), Vector2::new(0.0, -1.0))
+            }]
+        );
+    }
+
+    #[test]
+    fn golden_step_world_practice_fortress_cone() {
+        let match_settings = MatchSettings {
+            launch_direction: LaunchDirection::Cone,
+            random_seed: 42,
+            ..MatchSettings::default()
+        };
+        let mut state = create_world(BlockLayout::Fortress, GameMode::Practice, match_settings);
+
+        assert_eq!(block_ids(&state), (0..113).collect::<Vec<u32>>());
+        assert_eq!(
+            state
+                .world_data
+                .paddles
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+
+        run_golden_script(&mut state);
+
+        let world_data = &state.world_data;
+
+        assert_eq!(world_data.tick, 300);
+        assert_eq!(world_data.scores, None);
+        assert!(world_data.balls.is_empty());
+        assert_eq!(world_data.paddles[0].respawn_countdown, Some(1));
+        assert_eq!(block_ids(&state), (0..113).collect::<Vec<u32>>());
+
+        let damaged_ids: Vec<u32> = world_data
+            .blocks
+            .iter()
+            .filter(|b| b.hits_life < state.level_blocks[b.id as usize].hits_life)
+            .map(|b| b.id)
+            .collect();
+
+        assert_eq!(damaged_ids, vec![96]);
+    }
+
+    #[test]
+    fn ball_speed_is_preserved_over_random_bounces() {
+        for seed in 0..40 {
+            let mut random = SeededRandom::new(seed);
+            let game_mode = if seed % 2 == 0 {
+                GameMode::Practice
+            } else {
+                GameMode::Versus
+            };
+            let match_settings = MatchSettings {
+                bounce_jitter: if seed % 4 < 2 { 0.0 } else { 0.3 },
+                random_seed: seed,
+                ..MatchSettings::default()
+            };
+            let mut state = create_empty_world(game_mode, match_settings);
+
+            state.world_data.balls = vec![create_free_ball(
+                0,
+                Vector2::new(
+                    WORLD_WIDTH as f32 / 2.0 + random.next_signed_unit() * 800.0,
+                    WORLD_HEIGHT as f32 / 2.0 + random.next_signed_unit() * 400.0,
+                ),
+                Vector2::new(random.next_signed_unit(), random.next_signed_unit() + 2.0),
+            )];
+
+            let actions = [
+                PlayerAction::MoveLeft,
+                PlayerAction::MoveRight,
+                PlayerAction::Launch,
+            ];
+
+            for _ in 0..1200 {
+                let inputs: Vec<PlayerInput> = [0, 1]
+                    .into_iter()
+                    .map(|player_id| {
+                        let action_index = (random.next_u64() % actions.len() as u64) as usize;
+                        input(player_id, actions[action_index])
+                    })
+                    .collect();
+
+                step_world(&mut state, &inputs);
+
+                for ball in state.world_data.balls.iter().filter(|b| b.is_free) {
+                    assert!(
+                        (ball.velocity.magnitude() - 1.0).abs() < 1e-4,
+                        "seed {} tick {}: ball {} has speed {}",
+                        seed,
+                        state.world_data.tick,
+                        ball.id,
+                        ball.velocity.magnitude()
+                    );
+                }
+            }
+        }
+    }
+
+    fn step_ball_between_block_and_paddle(
+        block_overlap: f32,
+        paddle_overlap: f32,
+        velocity_y: f32,
+    ) -> GameState {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+        let paddle = paddle_position(&state, 0);
+        let ball_radius = state.match_settings.ball_radius;
+
+        let ball_y = paddle.y - PADDLE_HEIGHT as f32 / 2.0 - ball_radius + paddle_overlap;
+        let block_y = ball_y - ball_radius - BLOCK_SIZE as f32 / 2.0 + block_overlap;
+
+        state.world_data.blocks = vec![create_block(0, Vector2::new(paddle.x, block_y))];
+        state.world_data.balls = vec![create_free_ball(
+            0,
+            Vector2::new(paddle.x, ball_y),
+            Vector2::new(0.0, velocity_y),
+        )];
+
+        step_world(&mut state, &[]);
+
+        state
+    }
+
+    #[test]
+    fn ball_deeper_in_block_above_paddle_reflects_off_block_only() {
+        let state = step_ball_between_block_and_paddle(8.0, 5.0, -1.0);
+
+        assert!(state.world_data.blocks.is_empty());
+        assert_eq!(state.world_data.balls[0].velocity, Vector2::new(0.0, 1.0));
+        assert_eq!(state.world_data.balls[0].paddle_collision_cooldown_ticks, 0);
+    }
+
+    #[test]
+    fn ball_deeper_in_paddle_below_block_reflects_off_paddle_only() {
+        let state = step_ball_between_block_and_paddle(3.0, 6.0, 1.0);
+
+        assert_eq!(state.world_data.blocks.len(), 1);
+        assert_eq!(state.world_data.balls[0].velocity, Vector2::new(0.0, -1.0));
+        assert_eq!(
+            state.world_data.balls[0].paddle_collision_cooldown_ticks,
+            PADDLE_COLLISION_COOLDOWN_TICKS
+        );
+    }
+
+    fn ball_ids(state: &GameState) -> Vec<u8> {
+        let mut ids: Vec<u8> = state.world_data.balls.iter().map(|b| b.id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn each_player_starts_with_two_balls_and_launches_both() {
+        let match_settings = MatchSettings {
+            balls_per_player: 2,
+            ..MatchSettings::default()
+        };
+        let mut state = create_empty_world(GameMode::Versus, match_settings);
+
+        assert_eq!(ball_ids(&state), vec![0, 1, 2, 3]);
+
+        for player_id in [0, 1] {
+            let owned_balls = state
+                .world_data
+                .balls
+                .iter()
+                .filter(|b| b.owner == player_id);
+            assert_eq!(owned_balls.filter(|b| !b.is_free).count(), 2);
+        }
+
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+
+        for ball in &state.world_data.balls {
+            assert_eq!(ball.is_free, ball.owner == 0, "{:?}", ball);
+        }
+
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+        step_world(&mut state, &[input(1, PlayerAction::Launch)]);
+
+        assert_eq!(
+            state
+                .world_data
+                .balls
+                .iter()
+                .filter(|b| b.owner == 1 && b.is_free)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn respawned_ball_ids_stay_unique_over_many_respawns() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+
+        for _ in 0..260 {
+            for ball in state.world_data.balls.iter_mut().filter(|b| b.owner == 0) {
+                ball.position = Vector2::new(100.0, WORLD_HEIGHT as f32);
+                ball.velocity = Vector2::new(0.0, 1.0);
+                ball.is_free = true;
+            }
+
+            step_until_respawned(&mut state);
+
+            let ids = ball_ids(&state);
+            let mut unique_ids = ids.clone();
+            unique_ids.dedup();
+
+            assert_eq!(ids.len(), 2);
+            assert_eq!(ids, unique_ids);
+        }
+    }
+
+    #[test]
+    fn launch_finds_the_ball_by_owner_not_id() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+
+        let player_0_ball = state
+            .world_data
+            .balls
+            .iter()
+            .find(|b| b.owner == 0)
+            .unwrap();
+        assert_ne!(player_0_ball.id, 0);
+        let player_0_ball_id = player_0_ball.id;
+
+        step_world(&mut state, &[input(0, PlayerAction::Launch)]);
+
+        for ball in &state.world_data.balls {
+            assert_eq!(ball.is_free, ball.id == player_0_ball_id, "{:?}", ball);
+        }
+    }
+
+    #[test]
+    fn respawned_ball_keeps_its_owner() {
+        let mut state = create_empty_world(GameMode::Versus, MatchSettings::default());
+
+        state.world_data.balls = vec![
+            create_owned_ball(0, 0, Vector2::new(300.0, 500.0), Vector2::new(1.0, 0.0)),
+            create_owned_ball(1, 1, Vector2::new(100.0, 0.0), Vector2::new(0.0, -1.0)),
+        ];
+
+        step_until_respawned(&mut state);
+
+        let respawned_ball = state.world_data.balls.iter().find(|b| !b.is_free).unwrap();
+        let paddle = paddle_position(&state, 1);
+
+        assert_eq!(respawned_ball.owner, 1);
+        assert_eq!(respawned_ball.id, 1);
+        assert_eq!(respawned_ball.position.x, paddle.x);
+        assert!(respawned_ball.position.y > paddle.y);
+
+        step_world(&mut state, &[input(1, PlayerAction::Launch)]);
+
+        assert!(state.world_data.balls.iter().all(|b| b.is_free));
+    }
+}
@@ -1,2 +1,10 @@
+pub mod block_layouts;
+pub mod client_message;
 pub mod constants;
+pub mod game;
+pub mod match_settings;
+pub mod random;
+pub mod server_info;
+pub mod server_message;
+pub mod view;
 pub mod world_data;
@@ -0,0 +1,359 @@
+use crate::constants::{BALL_RADIUS, PADDLE_HEIGHT, WORLD_HEIGHT, WORLD_WIDTH};
+use crate::game::{
+    MAX_ARENA_EDGE_DEAD_ZONE, MAX_BALLS_PER_PLAYER, MAX_BALL_X_VELOCITY, MAX_SPLIT_PADDLE_GAP,
+    PADDLE_SPEED,
+};
+use serde::{Deserialize, Serialize};
+
+pub const GOAL_NOTCH_DEPTH: f32 = 8.0;
+pub const GOAL_NOTCH_HALF_WIDTH: f32 = 300.0;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum DeflectionCurve {
+    Linear,
+    Quadratic,
+    Clamped,
+}
+
+impl DeflectionCurve {
+    pub const ALL: [DeflectionCurve; 3] = [
+        DeflectionCurve::Linear,
+        DeflectionCurve::Quadratic,
+        DeflectionCurve::Clamped,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeflectionCurve::Linear => "linear",
+            DeflectionCurve::Quadratic => "quadratic",
+            DeflectionCurve::Clamped => "clamped",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum GoalLine {
+    Flat,
+    Notch,
+}
+
+impl GoalLine {
+    pub const ALL: [GoalLine; 2] = [GoalLine::Flat, GoalLine::Notch];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalLine::Flat => "flat",
+            GoalLine::Notch => "notch",
+        }
+    }
+
+    pub fn inset_at(&self, x: f32) -> f32 {
+        match self {
+            GoalLine::Flat => 0.0,
+            GoalLine::Notch => {
+                let distance_from_center = (x - WORLD_WIDTH as f32 / 2.0).abs();
+                GOAL_NOTCH_DEPTH * (1.0 - distance_from_center / GOAL_NOTCH_HALF_WIDTH).max(0.0)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum PaddleSideHit {
+    Reflect,
+    PassThrough,
+}
+
+impl PaddleSideHit {
+    pub const ALL: [PaddleSideHit; 2] = [PaddleSideHit::Reflect, PaddleSideHit::PassThrough];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaddleSideHit::Reflect => "reflect",
+            PaddleSideHit::PassThrough => "pass-through",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum LaunchDirection {
+    Straight,
+    Cone,
+    TowardConceder,
+}
+
+impl LaunchDirection {
+    pub const ALL: [LaunchDirection; 3] = [
+        LaunchDirection::Straight,
+        LaunchDirection::Cone,
+        LaunchDirection::TowardConceder,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LaunchDirection::Straight => "straight",
+            LaunchDirection::Cone => "cone",
+            LaunchDirection::TowardConceder => "toward-conceder",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MatchSettings {
+    pub gravity: f32,
+    pub ball_radius: f32,
+    pub collision_margin: f32,
+    pub arena_edge_dead_zone: f32,
+    pub paddle_offsets: [f32; 2],
+    pub require_ready: bool,
+    pub deflection_curve: DeflectionCurve,
+    pub goal_line: GoalLine,
+    pub paddle_side_hit: PaddleSideHit,
+    pub balls_per_player: u8,
+    pub training_ball: bool,
+    pub max_rally_seconds: Option<u32>,
+    pub paddle_acceleration: Option<f32>,
+    pub mercy_score_gap: Option<u32>,
+    pub bounce_jitter: f32,
+    pub random_seed: u64,
+    pub split_paddle_gap: Option<u32>,
+    pub paddle_block_collision: bool,
+    pub launch_direction: LaunchDirection,
+    pub paddle_speed: f32,
+    pub block_regeneration_seconds: Option<u32>,
+    pub breakthrough_seconds: Option<u32>,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            gravity: 0.0,
+            ball_radius: BALL_RADIUS as f32,
+            collision_margin: 0.0,
+            arena_edge_dead_zone: 0.0,
+            paddle_offsets: [PADDLE_HEIGHT as f32; 2],
+            require_ready: false,
+            deflection_curve: DeflectionCurve::Linear,
+            goal_line: GoalLine::Flat,
+            paddle_side_hit: PaddleSideHit::Reflect,
+            balls_per_player: 1,
+            training_ball: false,
+            max_rally_seconds: None,
+            paddle_acceleration: None,
+            mercy_score_gap: None,
+            bounce_jitter: 0.0,
+            random_seed: 0,
+            split_paddle_gap: None,
+            paddle_block_collision: false,
+            launch_direction: LaunchDirection::Straight,
+            paddle_speed: PADDLE_SPEED as f32,
+            block_regeneration_seconds: None,
+            breakthrough_seconds: None,
+        }
+    }
+}
+
+impl MatchSettings {
+    /// Rejects settings the game loop can't run with, so a bad combination is reported before
+    /// the match starts instead of surfacing as a panic or a stuck ball mid-run.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.gravity.is_finite() || self.gravity < 0.0 {
+            return Err(format!(
+                "Gravity must be a non-negative number, got {}",
+                self.gravity
+            ));
+        }
+
+        if !self.ball_radius.is_finite() || self.ball_radius <= 0.0 {
+            return Err(format!(
+                "Ball radius must be a positive number, got {}",
+                self.ball_radius
+            ));
+        }
+
+        if !self.collision_margin.is_finite() || self.ball_radius + self.collision_margin <= 0.0 {
+            return Err(format!(
+                "Collision margin ({}) must not shrink the ball radius ({}) to zero or below",
+                self.collision_margin, self.ball_radius
+            ));
+        }
+
+        if !(0.0..=MAX_ARENA_EDGE_DEAD_ZONE).contains(&self.arena_edge_dead_zone) {
+            return Err(format!(
+                "Arena edge dead zone must be between 0 and {}, got {}",
+                MAX_ARENA_EDGE_DEAD_ZONE, self.arena_edge_dead_zone
+            ));
+        }
+
+        let min_paddle_offset = PADDLE_HEIGHT as f32 / 2.0;
+        let max_paddle_offset = WORLD_HEIGHT as f32 / 2.0 - PADDLE_HEIGHT as f32;
+
+        for (player_id, offset) in self.paddle_offsets.iter().enumerate() {
+            if !(min_paddle_offset..=max_paddle_offset).contains(offset) {
+                return Err(format!(
+                    "Player {} paddle offset must be between {} and {}, got {}",
+                    player_id, min_paddle_offset, max_paddle_offset, offset
+                ));
+            }
+        }
+
+        let closest_paddle_edge =
+            self.paddle_offsets[0].min(self.paddle_offsets[1]) - PADDLE_HEIGHT as f32 / 2.0;
+
+        if self.goal_line == GoalLine::Notch && closest_paddle_edge <= GOAL_NOTCH_DEPTH {
+            return Err(format!(
+                "The notch goal line is {} deep, paddles must sit further from the edge than that",
+                GOAL_NOTCH_DEPTH
+            ));
+        }
+
+        if !(1..=MAX_BALLS_PER_PLAYER).contains(&self.balls_per_player) {
+            return Err(format!(
+                "Balls per player must be between 1 and {}, got {}",
+                MAX_BALLS_PER_PLAYER, self.balls_per_player
+            ));
+        }
+
+        if self.max_rally_seconds == Some(0) {
+            return Err("Max rally length must be greater than 0 seconds".to_string());
+        }
+
+        if let Some(paddle_acceleration) = self.paddle_acceleration {
+            if !paddle_acceleration.is_finite() || paddle_acceleration <= 0.0 {
+                return Err(format!(
+                    "Paddle acceleration must be a positive number, got {}",
+                    paddle_acceleration
+                ));
+            }
+        }
+
+        if !(0.0..=MAX_BALL_X_VELOCITY).contains(&self.bounce_jitter) {
+            return Err(format!(
+                "Bounce jitter must be between 0 and {}, got {}",
+                MAX_BALL_X_VELOCITY, self.bounce_jitter
+            ));
+        }
+
+        if !self.paddle_speed.is_finite() || self.paddle_speed <= 0.0 {
+            return Err(format!(
+                "Paddle speed must be a positive number, got {}",
+                self.paddle_speed
+            ));
+        }
+
+        if let Some(split_paddle_gap) = self.split_paddle_gap {
+            if !(1..=MAX_SPLIT_PADDLE_GAP).contains(&split_paddle_gap) {
+                return Err(format!(
+                    "Split paddle gap must be between 1 and {}, got {}",
+                    MAX_SPLIT_PADDLE_GAP, split_paddle_gap
+                ));
+            }
+        }
+
+        if self.block_regeneration_seconds == Some(0) {
+            return Err("Block regeneration delay must be greater than 0 seconds".to_string());
+        }
+
+        if self.breakthrough_seconds == Some(0) {
+            return Err("Breakthrough duration must be greater than 0 seconds".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type SettingsMutation = (&'static str, fn(&mut MatchSettings));
+
+    fn validate_with(change: impl FnOnce(&mut MatchSettings)) -> Result<(), String> {
+        let mut match_settings = MatchSettings::default();
+        change(&mut match_settings);
+        match_settings.validate()
+    }
+
+    #[test]
+    fn default_settings_are_valid() {
+        assert_eq!(MatchSettings::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn zero_values_are_rejected_where_they_would_stall_the_game() {
+        let invalid_changes: [SettingsMutation; 8] = [
+            ("Ball radius", |s| s.ball_radius = 0.0),
+            ("Balls per player", |s| s.balls_per_player = 0),
+            ("Max rally length", |s| s.max_rally_seconds = Some(0)),
+            ("Paddle acceleration", |s| s.paddle_acceleration = Some(0.0)),
+            ("Paddle speed", |s| s.paddle_speed = 0.0),
+            ("Split paddle gap", |s| s.split_paddle_gap = Some(0)),
+            ("Block regeneration delay", |s| {
+                s.block_regeneration_seconds = Some(0)
+            }),
+            ("Breakthrough duration", |s| {
+                s.breakthrough_seconds = Some(0)
+            }),
+        ];
+
+        for (message_start, change) in invalid_changes {
+            let error = validate_with(change).unwrap_err();
+            assert!(error.starts_with(message_start), "{}", error);
+        }
+    }
+
+    #[test]
+    fn zero_values_are_accepted_where_they_disable_a_feature() {
+        let valid_changes: [fn(&mut MatchSettings); 4] = [
+            |s| s.gravity = 0.0,
+            |s| s.collision_margin = 0.0,
+            |s| s.arena_edge_dead_zone = 0.0,
+            |s| s.bounce_jitter = 0.0,
+        ];
+
+        for change in valid_changes {
+            assert_eq!(validate_with(change), Ok(()));
+        }
+    }
+
+    #[test]
+    fn negative_values_are_rejected() {
+        let invalid_changes: [SettingsMutation; 7] = [
+            ("Gravity", |s| s.gravity = -1.0),
+            ("Ball radius", |s| s.ball_radius = -5.0),
+            ("Collision margin", |s| s.collision_margin = -s.ball_radius),
+            ("Arena edge dead zone", |s| s.arena_edge_dead_zone = -1.0),
+            ("Paddle acceleration", |s| {
+                s.paddle_acceleration = Some(-10.0)
+            }),
+            ("Bounce jitter", |s| s.bounce_jitter = -0.1),
+            ("Paddle speed", |s| s.paddle_speed = -300.0),
+        ];
+
+        for (message_start, change) in invalid_changes {
+            let error = validate_with(change).unwrap_err();
+            assert!(error.starts_with(message_start), "{}", error);
+        }
+
+        assert!(validate_with(|s| s.collision_margin = -1.0).is_ok());
+    }
+
+    #[test]
+    fn values_above_their_limits_are_rejected() {
+        assert!(validate_with(|s| s.arena_edge_dead_zone = MAX_ARENA_EDGE_DEAD_ZONE).is_ok());
+        assert!(
+            validate_with(|s| s.arena_edge_dead_zone = MAX_ARENA_EDGE_DEAD_ZONE + 1.0).is_err()
+        );
+        assert!(validate_with(|s| s.balls_per_player = MAX_BALLS_PER_PLAYER + 1).is_err());
+        assert!(validate_with(|s| s.bounce_jitter = MAX_BALL_X_VELOCITY + 0.1).is_err());
+        assert!(validate_with(|s| s.split_paddle_gap = Some(MAX_SPLIT_PADDLE_GAP + 1)).is_err());
+    }
+
+    #[test]
+    fn non_finite_values_are_rejected() {
+        assert!(validate_with(|s| s.gravity = f32::NAN).is_err());
+        assert!(validate_with(|s| s.ball_radius = f32::INFINITY).is_err());
+        assert!(validate_with(|s| s.arena_edge_dead_zone = f32::NAN).is_err());
+        assert!(validate_with(|s| s.paddle_speed = f32::NAN).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use crate::match_settings::MatchSettings;
+use serde::{Deserialize, Serialize};
+
+pub const PROTOCOL_VERSION: u16 = 4;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ServerInfo {
+    pub protocol_version: u16,
+    pub available_modes: Vec<String>,
+    pub active_mode: String,
+    pub available_layouts: Vec<String>,
+    pub active_layout: String,
+    pub arena_width: usize,
+    pub arena_height: usize,
+    pub player_capacity: u8,
+    pub match_settings: MatchSettings,
+}
@@ -0,0 +1,200 @@
+use crate::world_data::{Ball, Paddle, RegeneratingBlock, WorldData};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum DisconnectReason {
+    MatchEnded,
+    ServerShuttingDown,
+    Kicked(String),
+}
+
+impl DisconnectReason {
+    pub fn description(&self) -> String {
+        match self {
+            DisconnectReason::MatchEnded => "Match ended".to_string(),
+            DisconnectReason::ServerShuttingDown => "Server shutting down".to_string(),
+            DisconnectReason::Kicked(reason) => format!("Kicked: {}", reason),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct HudUpdate {
+    pub tick: u64,
+    pub survival_seconds: Option<f32>,
+    pub scores: Option<[u32; 2]>,
+    pub mercy_player: Option<u8>,
+    pub ready_players: Option<[bool; 2]>,
+    pub waiting_for_players: Option<u8>,
+    pub rally_countdown: Option<u8>,
+    pub match_countdown: Option<u8>,
+    pub reconnect_grace_seconds: Option<u16>,
+    pub is_paused: bool,
+}
+
+impl HudUpdate {
+    pub fn from_world_data(world_data: &WorldData) -> Self {
+        HudUpdate {
+            tick: world_data.tick,
+            survival_seconds: world_data.survival_seconds,
+            scores: world_data.scores,
+            mercy_player: world_data.mercy_player,
+            ready_players: world_data.ready_players,
+            waiting_for_players: world_data.waiting_for_players,
+            rally_countdown: world_data.rally_countdown,
+            match_countdown: world_data.match_countdown,
+            reconnect_grace_seconds: world_data.reconnect_grace_seconds,
+            is_paused: world_data.is_paused,
+        }
+    }
+
+    pub fn apply_to(&self, world_data: &mut WorldData) {
+        world_data.survival_seconds = self.survival_seconds;
+        world_data.scores = self.scores;
+        world_data.mercy_player = self.mercy_player;
+        world_data.ready_players = self.ready_players;
+        world_data.waiting_for_players = self.waiting_for_players;
+        world_data.rally_countdown = self.rally_countdown;
+        world_data.match_countdown = self.match_countdown;
+        world_data.reconnect_grace_seconds = self.reconnect_grace_seconds;
+        world_data.is_paused = self.is_paused;
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DynamicUpdate {
+    pub tick: u64,
+    pub regenerating_blocks: Vec<RegeneratingBlock>,
+    pub paddles: Vec<Paddle>,
+    pub balls: Vec<Ball>,
+    pub hud: HudUpdate,
+}
+
+impl DynamicUpdate {
+    pub fn from_world_data(world_data: &WorldData) -> Self {
+        DynamicUpdate {
+            tick: world_data.tick,
+            regenerating_blocks: world_data.regenerating_blocks.clone(),
+            paddles: world_data.paddles.clone(),
+            balls: world_data.balls.clone(),
+            hud: HudUpdate::from_world_data(world_data),
+        }
+    }
+
+    pub fn merge_with_static(self, static_world_data: &WorldData) -> WorldData {
+        let mut world_data = WorldData {
+            tick: self.tick,
+            blocks: static_world_data.blocks.clone(),
+            regenerating_blocks: self.regenerating_blocks,
+            paddles: self.paddles,
+            balls: self.balls,
+            layout_name: static_world_data.layout_name.clone(),
+            level_index: static_world_data.level_index,
+            survival_seconds: None,
+            scores: None,
+            mercy_player: None,
+            ready_players: None,
+            waiting_for_players: None,
+            rally_countdown: None,
+            match_countdown: None,
+            reconnect_grace_seconds: None,
+            is_paused: false,
+        };
+
+        self.hud.apply_to(&mut world_data);
+        world_data
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    WorldData(WorldData),
+    Dynamic(DynamicUpdate),
+    Hud(HudUpdate),
+    Disconnect(DisconnectReason),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world_data::tests::create_test_world_data;
+
+    fn round_trip(message: &ServerMessage) -> ServerMessage {
+        let bytes = rmp_serde::to_vec(message).unwrap();
+        rmp_serde::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn server_messages_round_trip() {
+        let world_data = create_test_world_data();
+
+        let messages = [
+            ServerMessage::WorldData(world_data.clone()),
+            ServerMessage::Dynamic(DynamicUpdate::from_world_data(&world_data)),
+            ServerMessage::Hud(HudUpdate::from_world_data(&world_data)),
+            ServerMessage::Disconnect(DisconnectReason::MatchEnded),
+            ServerMessage::Disconnect(DisconnectReason::ServerShuttingDown),
+            ServerMessage::Disconnect(DisconnectReason::Kicked("idle".to_string())),
+        ];
+
+        for message in messages {
+            assert_eq!(round_trip(&message), message);
+        }
+    }
+
+    #[test]
+    fn hud_update_changes_scores_but_not_block_positions() {
+        let mut world_data = create_test_world_data();
+        let blocks = world_data.blocks.clone();
+        let paddles = world_data.paddles.clone();
+        let balls = world_data.balls.clone();
+
+        let mut next_world_data = world_data.clone();
+        next_world_data.tick += 1;
+        next_world_data.scores = Some([4, 9]);
+        next_world_data.rally_countdown = Some(2);
+        next_world_data.blocks.clear();
+
+        HudUpdate::from_world_data(&next_world_data).apply_to(&mut world_data);
+
+        assert_eq!(world_data.scores, Some([4, 9]));
+        assert_eq!(world_data.rally_countdown, Some(2));
+        assert_eq!(world_data.blocks, blocks);
+        assert_eq!(world_data.paddles, paddles);
+        assert_eq!(world_data.balls, balls);
+    }
+
+    #[test]
+    fn dynamic_update_merged_with_static_state_restores_world_data() {
+        let static_world_data = create_test_world_data();
+
+        let mut world_data = static_world_data.clone();
+        world_data.tick += 5;
+        world_data.paddles[1].position.x += 40.0;
+        let ball_velocity = world_data.balls[0].velocity;
+        world_data.balls[0].position += ball_velocity * 25.0;
+        world_data.regenerating_blocks[0].progress = 0.75;
+        world_data.scores = Some([6, 2]);
+        world_data.is_paused = true;
+
+        let merged_world_data =
+            DynamicUpdate::from_world_data(&world_data).merge_with_static(&static_world_data);
+
+        assert_eq!(merged_world_data, world_data);
+    }
+
+    #[test]
+    fn dynamic_update_survives_encoding_before_merge() {
+        let world_data = create_test_world_data();
+
+        let message = round_trip(&ServerMessage::Dynamic(DynamicUpdate::from_world_data(
+            &world_data,
+        )));
+
+        let ServerMessage::Dynamic(update) = message else {
+            panic!("Expected a dynamic update");
+        };
+
+        assert_eq!(update.merge_with_static(&world_data), world_data);
+    }
+}
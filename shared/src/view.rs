@@ -0,0 +1,100 @@
+use crate::constants::{WORLD_HEIGHT, WORLD_WIDTH};
+use cgmath::Vector2;
+
+pub fn rotate_180_around_world_center(vector: Vector2<f32>) -> Vector2<f32> {
+    rotate_180_around_arena_center(vector, WORLD_WIDTH as f32, WORLD_HEIGHT as f32)
+}
+
+pub fn rotate_around_world_center(vector: Vector2<f32>, angle_radians: f32) -> Vector2<f32> {
+    let arena_center = Vector2::new(WORLD_WIDTH as f32 / 2.0, WORLD_HEIGHT as f32 / 2.0);
+    let translated = vector - arena_center;
+    let (sin, cos) = angle_radians.sin_cos();
+    let rotated = Vector2::new(
+        translated.x * cos - translated.y * sin,
+        translated.x * sin + translated.y * cos,
+    );
+    arena_center + rotated
+}
+
+pub fn rotate_180_around_arena_center(
+    vector: Vector2<f32>,
+    arena_width: f32,
+    arena_height: f32,
+) -> Vector2<f32> {
+    let arena_center = Vector2::new(arena_width / 2.0, arena_height / 2.0);
+    let translated = vector - arena_center;
+    let rotated = Vector2::new(-translated.x, -translated.y);
+    arena_center + rotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: f32 = WORLD_WIDTH as f32;
+    const HEIGHT: f32 = WORLD_HEIGHT as f32;
+
+    #[test]
+    fn world_center_stays_in_place() {
+        let center = Vector2::new(WIDTH / 2.0, HEIGHT / 2.0);
+
+        assert_eq!(rotate_180_around_world_center(center), center);
+    }
+
+    #[test]
+    fn corners_map_to_opposite_corners() {
+        let corners = [
+            (Vector2::new(0.0, 0.0), Vector2::new(WIDTH, HEIGHT)),
+            (Vector2::new(WIDTH, 0.0), Vector2::new(0.0, HEIGHT)),
+            (Vector2::new(0.0, HEIGHT), Vector2::new(WIDTH, 0.0)),
+            (Vector2::new(WIDTH, HEIGHT), Vector2::new(0.0, 0.0)),
+        ];
+
+        for (corner, opposite_corner) in corners {
+            assert_eq!(rotate_180_around_world_center(corner), opposite_corner);
+        }
+    }
+
+    #[test]
+    fn rotating_twice_returns_the_original_point() {
+        for point in [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(123.5, 987.25),
+            Vector2::new(WIDTH / 2.0, 10.0),
+            Vector2::new(-40.0, HEIGHT + 40.0),
+        ] {
+            let rotated_twice =
+                rotate_180_around_world_center(rotate_180_around_world_center(point));
+
+            assert_eq!(rotated_twice, point);
+        }
+    }
+
+    #[test]
+    fn non_square_arena_rotates_each_axis_around_its_own_center() {
+        let (width, height) = (800.0, 200.0);
+
+        assert_eq!(
+            rotate_180_around_arena_center(Vector2::new(400.0, 100.0), width, height),
+            Vector2::new(400.0, 100.0)
+        );
+        assert_eq!(
+            rotate_180_around_arena_center(Vector2::new(0.0, 0.0), width, height),
+            Vector2::new(800.0, 200.0)
+        );
+        assert_eq!(
+            rotate_180_around_arena_center(Vector2::new(100.0, 150.0), width, height),
+            Vector2::new(700.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn half_turn_matches_the_180_degree_flip() {
+        let point = Vector2::new(300.0, 200.0);
+        let rotated = rotate_around_world_center(point, std::f32::consts::PI);
+        let flipped = rotate_180_around_world_center(point);
+
+        assert!((rotated.x - flipped.x).abs() < 1e-3);
+        assert!((rotated.y - flipped.y).abs() < 1e-3);
+    }
+}
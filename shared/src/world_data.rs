@@ -1,25 +1,52 @@
 use cgmath::Vector2;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct WorldData {
+    pub tick: u64,
     pub blocks: Vec<Block>,
-    pub paddles: [Paddle; 2],
+    pub regenerating_blocks: Vec<RegeneratingBlock>,
+    pub paddles: Vec<Paddle>,
     pub balls: Vec<Ball>,
+    pub layout_name: String,
+    pub level_index: Option<u8>,
+    pub survival_seconds: Option<f32>,
+    pub scores: Option<[u32; 2]>,
+    pub mercy_player: Option<u8>,
+    pub ready_players: Option<[bool; 2]>,
+    pub waiting_for_players: Option<u8>,
+    pub rally_countdown: Option<u8>,
+    pub match_countdown: Option<u8>,
+    pub reconnect_grace_seconds: Option<u16>,
+    pub is_paused: bool,
 }
 
 impl Clone for WorldData {
     fn clone(&self) -> Self {
         WorldData {
+            tick: self.tick,
             blocks: self.blocks.clone(),
+            regenerating_blocks: self.regenerating_blocks.clone(),
             paddles: self.paddles.clone(),
             balls: self.balls.clone(),
+            layout_name: self.layout_name.clone(),
+            level_index: self.level_index,
+            survival_seconds: self.survival_seconds,
+            scores: self.scores,
+            mercy_player: self.mercy_player,
+            ready_players: self.ready_players,
+            waiting_for_players: self.waiting_for_players,
+            rally_countdown: self.rally_countdown,
+            match_countdown: self.match_countdown,
+            reconnect_grace_seconds: self.reconnect_grace_seconds,
+            is_paused: self.is_paused,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct Block {
+    pub id: u32,
     pub position: Vector2<f32>,
     pub hits_life: usize,
 }
@@ -27,16 +54,35 @@ pub struct Block {
 impl Clone for Block {
     fn clone(&self) -> Self {
         Block {
+            id: self.id,
             position: self.position,
             hits_life: self.hits_life,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct RegeneratingBlock {
+    pub id: u32,
+    pub position: Vector2<f32>,
+    pub progress: f32,
+}
+
+impl Clone for RegeneratingBlock {
+    fn clone(&self) -> Self {
+        RegeneratingBlock {
+            id: self.id,
+            position: self.position,
+            progress: self.progress,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct Paddle {
     pub id: u8,
     pub position: Vector2<f32>,
+    pub respawn_countdown: Option<u8>,
 }
 
 impl Clone for Paddle {
@@ -44,25 +90,152 @@ impl Clone for Paddle {
         Paddle {
             id: self.id,
             position: self.position,
+            respawn_countdown: self.respawn_countdown,
         }
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
 pub struct Ball {
     pub id: u8,
+    pub owner: u8,
     pub position: Vector2<f32>,
     pub velocity: Vector2<f32>,
     pub is_free: bool,
+    pub scoring: bool,
+    pub attached_ticks: usize,
+    pub serve_countdown: Option<u8>,
+    pub breakthrough_ticks: usize,
+    pub paddle_collision_cooldown_ticks: usize,
 }
 
 impl Clone for Ball {
     fn clone(&self) -> Self {
         Ball {
             id: self.id,
+            owner: self.owner,
             position: self.position,
             velocity: self.velocity,
             is_free: self.is_free,
+            scoring: self.scoring,
+            attached_ticks: self.attached_ticks,
+            serve_countdown: self.serve_countdown,
+            breakthrough_ticks: self.breakthrough_ticks,
+            paddle_collision_cooldown_ticks: self.paddle_collision_cooldown_ticks,
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn create_test_world_data() -> WorldData {
+        WorldData {
+            tick: 1234,
+            blocks: vec![
+                Block {
+                    id: 0,
+                    position: Vector2::new(100.0, 500.0),
+                    hits_life: 1,
+                },
+                Block {
+                    id: 7,
+                    position: Vector2::new(151.0, 551.0),
+                    hits_life: 2,
+                },
+            ],
+            regenerating_blocks: vec![RegeneratingBlock {
+                id: 3,
+                position: Vector2::new(202.0, 500.0),
+                progress: 0.25,
+            }],
+            paddles: vec![
+                Paddle {
+                    id: 1,
+                    position: Vector2::new(960.0, 20.0),
+                    respawn_countdown: None,
+                },
+                Paddle {
+                    id: 0,
+                    position: Vector2::new(480.5, 1060.0),
+                    respawn_countdown: Some(2),
+                },
+            ],
+            balls: vec![
+                Ball {
+                    id: 0,
+                    owner: 0,
+                    position: Vector2::new(700.25, 640.75),
+                    velocity: Vector2::new(0.6, -0.8),
+                    is_free: true,
+                    scoring: true,
+                    attached_ticks: 0,
+                    serve_countdown: None,
+                    breakthrough_ticks: 30,
+                    paddle_collision_cooldown_ticks: 0,
+                },
+                Ball {
+                    id: 1,
+                    owner: 1,
+                    position: Vector2::new(960.0, 40.0),
+                    velocity: Vector2::new(0.0, 0.0),
+                    is_free: false,
+                    scoring: true,
+                    attached_ticks: 0,
+                    serve_countdown: Some(3),
+                    breakthrough_ticks: 0,
+                    paddle_collision_cooldown_ticks: 0,
+                },
+            ],
+            layout_name: "pyramid".to_string(),
+            level_index: Some(1),
+            survival_seconds: None,
+            scores: Some([4, 2]),
+            mercy_player: Some(1),
+            ready_players: Some([true, false]),
+            waiting_for_players: None,
+            rally_countdown: Some(5),
+            match_countdown: None,
+            reconnect_grace_seconds: Some(12),
+            is_paused: false,
+        }
+    }
+
+    fn round_trip(world_data: &WorldData) -> WorldData {
+        let bytes = rmp_serde::to_vec(world_data).unwrap();
+        rmp_serde::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn world_data_round_trips() {
+        let world_data = create_test_world_data();
+
+        assert_eq!(round_trip(&world_data), world_data);
+    }
+
+    #[test]
+    fn world_data_without_balls_or_blocks_round_trips() {
+        let mut world_data = create_test_world_data();
+        world_data.balls.clear();
+        world_data.blocks.clear();
+        world_data.regenerating_blocks.clear();
+        world_data.scores = None;
+        world_data.is_paused = true;
+
+        assert_eq!(round_trip(&world_data), world_data);
+    }
+
+    #[test]
+    fn ball_tick_counters_round_trip() {
+        let mut world_data = create_test_world_data();
+        world_data.balls[1].attached_ticks = 90;
+        world_data.balls[0].paddle_collision_cooldown_ticks = 4;
+
+        let received = round_trip(&world_data);
+
+        assert_eq!(received.balls[1].attached_ticks, 90);
+        assert_eq!(received.balls[0].paddle_collision_cooldown_ticks, 4);
+        assert_eq!(received, world_data);
+    }
+}